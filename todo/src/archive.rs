@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::base::TodoItem;
+use crate::utils::{self, Store};
+
+/// Age in days after which completed tasks leave the history, by default.
+pub const ARCHIVE_DAYS: u32 = 30;
+/// Age in days after which archived tasks are purged, by default.
+pub const RETENTION_DAYS: u32 = 365;
+
+/// Time a task was completed, its last change for tasks saved without one.
+fn completed_at(item: &TodoItem) -> DateTime<Utc> {
+    item.completed_at.unwrap_or(item.updated_at)
+}
+
+/// Month a task was completed in, as `YYYY-MM`, naming its archive.
+fn month(item: &TodoItem) -> String {
+    completed_at(item)
+        .with_timezone(&Local)
+        .format("%Y-%m")
+        .to_string()
+}
+
+/// Split the history between the tasks to keep and the done ones completed before `cutoff`,
+/// grouped by month of completion.
+fn split(
+    history: Vec<TodoItem>,
+    cutoff: DateTime<Utc>,
+) -> (Vec<TodoItem>, BTreeMap<String, Vec<TodoItem>>) {
+    let mut kept = Vec::new();
+    let mut archived: BTreeMap<String, Vec<TodoItem>> = BTreeMap::new();
+    for item in history {
+        // tasks reopened in the history wait for the next flush
+        if item.is_done() && completed_at(&item) < cutoff {
+            archived.entry(month(&item)).or_default().push(item);
+        } else {
+            kept.push(item);
+        }
+    }
+    (kept, archived)
+}
+
+/// Move the done tasks of the history completed before `cutoff` to their monthly archive,
+/// returning how many were moved.
+///
+/// The archives are written first, so that a failure leaves the history untouched.
+pub fn archive(
+    store: &Store,
+    topic: &str,
+    history: &mut Vec<TodoItem>,
+    cutoff: DateTime<Utc>,
+) -> Result<usize, Box<dyn Error>> {
+    let (kept, archived) = split(std::mem::take(history), cutoff);
+    let result = archived.iter().try_for_each(|(month, items)| {
+        let file = utils::archive_file(topic, month);
+        let mut archive = store.load_todo_items(&file)?;
+        archive.extend(items.iter().cloned());
+        store.save_list(&archive, &file)?;
+        Ok(())
+    });
+    *history = kept;
+    match result {
+        Ok(()) => Ok(archived.values().map(Vec::len).sum()),
+        Err(err) => {
+            history.extend(archived.into_values().flatten());
+            Err(err)
+        }
+    }
+}
+
+/// Archived tasks of a topic, with the month of their archive, the latest month first.
+pub fn load(store: &Store, topic: &str) -> Result<Vec<(String, TodoItem)>, Box<dyn Error>> {
+    let mut entries = Vec::new();
+    for month in store.list_archives(topic).into_iter().rev() {
+        let items = store.load_todo_items(&utils::archive_file(topic, &month))?;
+        entries.extend(items.into_iter().map(|item| (month.clone(), item)));
+    }
+    Ok(entries)
+}
+
+/// Remove a task from the archive of a month, deleting the archive once empty.
+///
+/// The task is matched as a whole, as archives written before ids were kept unique may hold
+/// several tasks with the same id.
+pub fn remove(
+    store: &Store,
+    topic: &str,
+    month: &str,
+    task: &TodoItem,
+) -> Result<(), Box<dyn Error>> {
+    let file = utils::archive_file(topic, month);
+    let mut items = store.load_todo_items(&file)?;
+    if let Some(index) = items.iter().position(|item| item == task) {
+        items.remove(index);
+    }
+    if items.is_empty() {
+        store.delete_archive(topic, month)?;
+    } else {
+        store.save_list(&items, &file)?;
+    }
+    Ok(())
+}
+
+/// Delete the archived tasks completed before `cutoff`, returning how many were deleted.
+pub fn purge(store: &Store, topic: &str, cutoff: DateTime<Utc>) -> Result<usize, Box<dyn Error>> {
+    let mut count = 0;
+    for month in store.list_archives(topic) {
+        let file = utils::archive_file(topic, &month);
+        let mut items = store.load_todo_items(&file)?;
+        let before = items.len();
+        items.retain(|item| completed_at(item) >= cutoff);
+        count += before - items.len();
+        if items.is_empty() {
+            store.delete_archive(topic, &month)?;
+        } else if items.len() < before {
+            store.save_list(&items, &file)?;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::Status;
+    use crate::utils::TempFolder;
+    use chrono::TimeDelta;
+
+    fn done(name: &str, completed_at: DateTime<Utc>) -> TodoItem {
+        let mut item = TodoItem::new(Status::Completed, name, "");
+        item.completed_at = Some(completed_at);
+        item
+    }
+
+    #[test]
+    fn test_archive_restore_and_purge() {
+        let folder = TempFolder::new("archive");
+        let store = folder.store();
+        let now = Utc::now();
+        let mut old = done("old", now - TimeDelta::days(400));
+        old.id = 1;
+        let mut recent = done("recent", now - TimeDelta::days(40));
+        recent.id = 2;
+        let mut reopened = done("reopened", now - TimeDelta::days(40));
+        reopened.set_status(Status::Todo);
+        let mut history = vec![old, recent, done("today", now), reopened];
+
+        let archived = archive(&store, "test", &mut history, now - TimeDelta::days(30)).unwrap();
+        assert_eq!(archived, 2);
+        let names: Vec<&str> = history.iter().map(|item| item.todo.as_str()).collect();
+        assert_eq!(names, ["today", "reopened"]);
+        assert_eq!(store.list_archives("test").len(), 2);
+        // archives belong to their topic
+        assert!(store.list_archives(utils::DEFAULT_TOPIC).is_empty());
+
+        let entries = load(&store, "test").unwrap();
+        let names: Vec<&str> = entries.iter().map(|(_, item)| item.todo.as_str()).collect();
+        assert_eq!(names, ["recent", "old"]);
+
+        let purged = purge(&store, "test", now - TimeDelta::days(365)).unwrap();
+        assert_eq!(purged, 1);
+
+        // archives written before ids were kept unique may repeat one
+        let mut reused = done("reused", now - TimeDelta::days(40));
+        reused.id = 2;
+        let mut history = vec![reused];
+        archive(&store, "test", &mut history, now - TimeDelta::days(30)).unwrap();
+        let (month, recent) = &load(&store, "test").unwrap()[0];
+        assert_eq!(recent.todo, "recent");
+        remove(&store, "test", month, recent).unwrap();
+        let entries = load(&store, "test").unwrap();
+        let names: Vec<&str> = entries.iter().map(|(_, item)| item.todo.as_str()).collect();
+        assert_eq!(names, ["reused"]);
+        let (month, reused) = &entries[0];
+        remove(&store, "test", month, reused).unwrap();
+        assert!(store.list_archives("test").is_empty());
+    }
+}
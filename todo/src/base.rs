@@ -1,13 +1,61 @@
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use crate::recurrence::Recurrence;
+use crate::tree;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct TodoItem {
+    /// Unique within a topic and never given again, `0` until assigned by `assign_ids`.
+    #[serde(default)]
+    pub id: u64,
     pub todo: String,
     pub info: String,
     pub status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Rule creating a new instance of the task when it is flushed once completed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Periods spent in progress, the last one still running while the task is in progress.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sessions: Vec<Session>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TodoItem>,
+    /// Whether the children are hidden in the list.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub collapsed: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Time tracked on a task while it was in progress.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Session {
+    pub start: DateTime<Utc>,
+    /// `None` while the session is running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Utc>>,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ValueEnum,
+)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Status {
     Todo,
@@ -15,12 +63,311 @@ pub enum Status {
     Completed,
 }
 
+/// Task priority, `P0` being the most urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Priority {
+    P0,
+    P1,
+    P2,
+    P3,
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::P0 => "P0",
+            Self::P1 => "P1",
+            Self::P2 => "P2",
+            Self::P3 => "P3",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "P0" | "0" => Ok(Self::P0),
+            "P1" | "1" => Ok(Self::P1),
+            "P2" | "2" => Ok(Self::P2),
+            "P3" | "3" => Ok(Self::P3),
+            other => Err(format!("unknown priority '{other}', expected P0 to P3")),
+        }
+    }
+}
+
 impl TodoItem {
     pub fn new(status: Status, todo: &str, info: &str) -> Self {
+        let now = Utc::now();
         Self {
+            id: 0,
             status,
             todo: todo.to_string(),
             info: info.to_string(),
+            due: None,
+            priority: None,
+            tags: Vec::new(),
+            recurrence: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: (status == Status::Completed).then_some(now),
+            sessions: Vec::new(),
+            children: Vec::new(),
+            collapsed: false,
+        }
+    }
+
+    /// Number of completed children over the number of children, `None` without children.
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        if self.children.is_empty() {
+            return None;
+        }
+        let completed = self
+            .children
+            .iter()
+            .filter(|child| child.status == Status::Completed)
+            .count();
+        Some((completed, self.children.len()))
+    }
+
+    /// Whether the item and all of its descendants are completed.
+    pub fn is_done(&self) -> bool {
+        self.status == Status::Completed && self.children.iter().all(TodoItem::is_done)
+    }
+
+    /// Change the status, keeping the timestamps up to date.
+    ///
+    /// A session starts when the task goes in progress, and stops when it leaves that status.
+    pub fn set_status(&mut self, status: Status) {
+        let now = Utc::now();
+        if status == Status::Completed && self.status != Status::Completed {
+            self.completed_at = Some(now);
+        } else if status != Status::Completed {
+            self.completed_at = None;
+        }
+        if status == Status::InProgress && self.status != Status::InProgress {
+            self.sessions.push(Session {
+                start: now,
+                end: None,
+            });
+        } else if status != Status::InProgress {
+            for session in &mut self.sessions {
+                session.end.get_or_insert(now);
+            }
+        }
+        self.status = status;
+        self.updated_at = now;
+    }
+
+    /// Start of the running session, if the timer is running.
+    pub fn running_since(&self) -> Option<DateTime<Utc>> {
+        self.sessions
+            .last()
+            .filter(|session| session.end.is_none())
+            .map(|session| session.start)
+    }
+
+    /// Time spent in progress after `since`, the running session counting until `now`.
+    pub fn time_spent(&self, since: Option<DateTime<Utc>>, now: DateTime<Utc>) -> TimeDelta {
+        self.sessions
+            .iter()
+            .map(|session| {
+                let start = since.map_or(session.start, |since| session.start.max(since));
+                let end = session.end.unwrap_or(now);
+                (end - start).max(TimeDelta::zero())
+            })
+            .fold(TimeDelta::zero(), |total, spent| total + spent)
+    }
+
+    /// Round-robin status order used when toggling an item.
+    pub fn next_status(&self) -> Status {
+        match self.status {
+            Status::Completed => Status::Todo,
+            Status::Todo => Status::InProgress,
+            Status::InProgress => Status::Completed,
         }
     }
+
+    /// Fresh instance of a recurring task, due on the date following the current one.
+    pub fn next_occurrence(&self, today: NaiveDate) -> Option<TodoItem> {
+        let due = self.recurrence.as_ref()?.next_due(self.due, today)?;
+        let mut next = self.reopened();
+        next.due = Some(due);
+        Some(next)
+    }
+
+    /// Copy of the task and its subtasks, as new open tasks without ids.
+    fn reopened(&self) -> TodoItem {
+        let mut item = TodoItem::new(Status::Todo, &self.todo, &self.info);
+        item.due = self.due;
+        item.priority = self.priority;
+        item.tags = self.tags.clone();
+        item.recurrence = self.recurrence.clone();
+        item.children = self.children.iter().map(TodoItem::reopened).collect();
+        item.collapsed = self.collapsed;
+        item
+    }
+
+    /// An open task is overdue once its due date is in the past.
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        self.status != Status::Completed && self.due.is_some_and(|due| due < today)
+    }
+}
+
+/// Split a comma separated list of tags, dropping empty entries.
+pub fn parse_tags(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Move completed items to the history, and items reopened in the history back to the todo
+/// list.
+///
+/// A parent only counts as completed once all of its children are. Recurring tasks leave a new
+/// instance in the todo list, whose id is left to `assign_ids`.
+pub fn flush(
+    todo: Vec<TodoItem>,
+    history: Vec<TodoItem>,
+    today: NaiveDate,
+) -> (Vec<TodoItem>, Vec<TodoItem>) {
+    let (mut done, mut open): (Vec<_>, Vec<_>) = history.into_iter().partition(TodoItem::is_done);
+    let (todo_done, todo_open): (Vec<_>, Vec<_>) = todo.into_iter().partition(TodoItem::is_done);
+    open.extend(todo_open);
+    open.extend(
+        todo_done
+            .iter()
+            .filter_map(|item| item.next_occurrence(today)),
+    );
+    done.extend(todo_done);
+    (open, done)
+}
+
+/// Id following the highest one of the topic, and `last_id`, the highest one ever given in it.
+pub fn next_id(todo: &[TodoItem], history: &[TodoItem], last_id: u64) -> u64 {
+    let mut max_id = last_id;
+    for items in [todo, history] {
+        tree::for_each(items, &mut |item| max_id = max_id.max(item.id));
+    }
+    max_id + 1
+}
+
+/// Give an id to the items loaded without one, after the highest id ever given in the topic,
+/// so that the ids of deleted or archived tasks are not given again.
+///
+/// `last_id` is raised to the highest id of the topic.
+pub fn assign_ids(todo: &mut [TodoItem], history: &mut [TodoItem], last_id: &mut u64) {
+    let mut next_id = next_id(todo, history, *last_id);
+    for items in [todo, history] {
+        tree::for_each_mut(items, &mut |item| {
+            if item.id == 0 {
+                item.id = next_id;
+                next_id += 1;
+            }
+        });
+    }
+    *last_id = next_id - 1;
+}
+
+/// Duration of a timer, as `H:MM:SS`.
+pub fn format_duration(duration: TimeDelta) -> String {
+    let seconds = duration.num_seconds().max(0);
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Human readable duration since `since`, such as "3 days".
+pub fn format_age(since: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let age = now - since;
+    if age.num_days() > 0 {
+        format!("{} days", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("{} hours", age.num_hours())
+    } else if age.num_minutes() > 0 {
+        format!("{} minutes", age.num_minutes())
+    } else {
+        "less than a minute".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sessions_follow_status() {
+        let mut item = TodoItem::new(Status::Todo, "task", "");
+        item.set_status(Status::InProgress);
+        assert!(item.running_since().is_some());
+        // staying in progress keeps the same session
+        item.set_status(Status::InProgress);
+        item.set_status(Status::Completed);
+        assert_eq!(item.sessions.len(), 1);
+        assert!(item.running_since().is_none());
+
+        let start = item.sessions[0].start;
+        item.sessions[0].end = Some(start + TimeDelta::minutes(90));
+        item.sessions.push(Session {
+            start: start + TimeDelta::hours(3),
+            end: None,
+        });
+        let now = start + TimeDelta::hours(4);
+        assert_eq!(item.time_spent(None, now), TimeDelta::minutes(150));
+        let since = start + TimeDelta::hours(1);
+        assert_eq!(item.time_spent(Some(since), now), TimeDelta::minutes(90));
+        assert_eq!(format_duration(TimeDelta::minutes(150)), "2:30:00");
+    }
+
+    #[test]
+    fn test_flush_waits_for_children() {
+        let mut waiting = TodoItem::new(Status::Completed, "waiting", "");
+        waiting.children = vec![
+            TodoItem::new(Status::Completed, "done child", ""),
+            TodoItem::new(Status::Todo, "open child", ""),
+        ];
+        let mut done = TodoItem::new(Status::Completed, "done", "");
+        done.children = vec![TodoItem::new(Status::Completed, "done child", "")];
+        let mut reopened = TodoItem::new(Status::Completed, "reopened", "");
+        reopened.children = vec![TodoItem::new(Status::InProgress, "reopened child", "")];
+
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let (todo, history) = flush(vec![waiting, done], vec![reopened], today);
+        let names = |items: &[TodoItem]| -> Vec<String> {
+            items.iter().map(|item| item.todo.clone()).collect()
+        };
+        // a completed parent stays until all of its children are completed
+        assert_eq!(names(&todo), ["reopened", "waiting"]);
+        assert_eq!(names(&history), ["done"]);
+        assert_eq!(history[0].children.len(), 1);
+    }
+
+    #[test]
+    fn test_ids_are_not_given_again() {
+        let mut todo = vec![TodoItem::new(Status::Todo, "a", "")];
+        let mut last_id = 0;
+        assign_ids(&mut todo, &mut [], &mut last_id);
+        assert_eq!((todo[0].id, last_id), (1, 1));
+
+        // the id of a deleted task stays used
+        todo.push(TodoItem::new(Status::Todo, "b", ""));
+        assign_ids(&mut todo, &mut [], &mut last_id);
+        todo.pop();
+        todo.push(TodoItem::new(Status::Todo, "c", ""));
+        assign_ids(&mut todo, &mut [], &mut last_id);
+        assert_eq!(todo[1].id, 3);
+        assert_eq!(next_id(&todo, &[], last_id), 4);
+
+        // ids written by hand raise the last one
+        todo[0].id = 10;
+        assign_ids(&mut todo, &mut [], &mut last_id);
+        assert_eq!(last_id, 10);
+    }
 }
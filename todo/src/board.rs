@@ -0,0 +1,106 @@
+use ratatui::widgets::ListState;
+
+use crate::base::{Status, TodoItem};
+use crate::filter::Filter;
+
+/// Statuses shown as the columns of the board, from left to right.
+pub const COLUMNS: [Status; 3] = [Status::Todo, Status::InProgress, Status::Completed];
+
+/// Cursor of the board, whose cards are the top-level tasks of the todo list grouped by status.
+#[derive(Debug, Default)]
+pub struct Board {
+    /// Index in `COLUMNS` of the focused column.
+    pub column: usize,
+    /// Selected card of each column.
+    pub states: [ListState; 3],
+}
+
+impl Board {
+    /// Indexes in `items` of the cards of a column shown once the filter is applied.
+    pub fn cards(items: &[TodoItem], status: Status, filter: &Filter) -> Vec<usize> {
+        items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.status == status && filter.matches(item))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Index in `items` of the selected card of the focused column.
+    pub fn selected(&self, items: &[TodoItem], filter: &Filter) -> Option<usize> {
+        let row = self.states[self.column].selected()?;
+        Self::cards(items, COLUMNS[self.column], filter)
+            .get(row)
+            .copied()
+    }
+
+    /// Focus the column of the card at `index` in `items` and select it.
+    pub fn select(&mut self, index: usize, items: &[TodoItem], filter: &Filter) {
+        let Some(item) = items.get(index) else {
+            return;
+        };
+        let Some(column) = COLUMNS.iter().position(|status| *status == item.status) else {
+            return;
+        };
+        self.column = column;
+        let row = Self::cards(items, item.status, filter)
+            .iter()
+            .position(|i| *i == index);
+        self.states[column].select(row);
+    }
+
+    /// Focus the column on the left or on the right, selecting its first card if none was.
+    pub fn focus(&mut self, offset: isize) {
+        let Some(column) = self
+            .column
+            .checked_add_signed(offset)
+            .filter(|column| *column < COLUMNS.len())
+        else {
+            return;
+        };
+        self.column = column;
+        if self.states[column].selected().is_none() {
+            self.states[column].select_first();
+        }
+    }
+
+    pub fn state_mut(&mut self) -> &mut ListState {
+        &mut self.states[self.column]
+    }
+}
+
+/// Status on the left or on the right of `status` on the board, if any.
+pub fn neighbour(status: Status, offset: isize) -> Option<Status> {
+    let column = COLUMNS.iter().position(|s| *s == status)?;
+    COLUMNS.get(column.checked_add_signed(offset)?).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_selection() {
+        let items = vec![
+            TodoItem::new(Status::Todo, "a", ""),
+            TodoItem::new(Status::Completed, "b", ""),
+            TodoItem::new(Status::Todo, "c", ""),
+        ];
+        let filter = Filter::default();
+        assert_eq!(Board::cards(&items, Status::Todo, &filter), [0, 2]);
+
+        let mut board = Board::default();
+        board.select(2, &items, &filter);
+        assert_eq!(board.selected(&items, &filter), Some(2));
+        board.focus(1);
+        // the empty column has nothing to select
+        assert_eq!(board.selected(&items, &filter), None);
+        board.focus(1);
+        assert_eq!(board.selected(&items, &filter), Some(1));
+        board.focus(1);
+        assert_eq!(board.column, 2);
+
+        assert_eq!(neighbour(Status::Todo, 1), Some(Status::InProgress));
+        assert_eq!(neighbour(Status::Todo, -1), None);
+    }
+}
@@ -0,0 +1,409 @@
+use chrono::{DateTime, Local, NaiveDate, TimeDelta, Utc};
+use clap::{Subcommand, ValueEnum};
+use color_eyre::{eyre::eyre, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::archive::{self, ARCHIVE_DAYS, RETENTION_DAYS};
+use crate::base::{self, parse_tags, Priority, Status, TodoItem};
+use crate::ical;
+use crate::markdown::{self, IN_PROGRESS_MARKER};
+use crate::recurrence::Recurrence;
+use crate::scan::{self, CODE_TOPIC};
+use crate::tree::{self, ItemPath};
+use crate::utils::{self, Store};
+
+/// Non-interactive commands, working on the same files as the TUI.
+///
+/// Tasks are identified by their stable id, as printed by `list`.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Add a task to the todo list
+    Add {
+        name: String,
+        #[arg(short, long, default_value = "")]
+        info: String,
+        /// Due date, as YYYY-MM-DD
+        #[arg(short, long)]
+        due: Option<NaiveDate>,
+        /// Priority, from P0 to P3
+        #[arg(short, long)]
+        priority: Option<Priority>,
+        /// Comma separated tags
+        #[arg(long, default_value = "")]
+        tags: String,
+        /// Id of the task to add this one to, as a subtask
+        #[arg(long)]
+        parent: Option<u64>,
+        /// Repeat the task once flushed: daily, weekly, monthly, yearly, "every 2 weeks" or a
+        /// cron rule such as "0 9 * * mon"
+        #[arg(short, long)]
+        repeat: Option<Recurrence>,
+    },
+    /// Print the todo list, subtasks included
+    List {
+        /// Only print the tasks with this status, along with their parents for context, printed
+        /// without an id
+        #[arg(short, long)]
+        status: Option<Status>,
+        /// Print the items as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Mark a task as completed
+    Done { id: u64 },
+    /// Delete a task
+    Rm { id: u64 },
+    /// Move completed tasks to the history
+    Flush,
+    /// Add the `- [ ]` / `- [x]` checklist items of a Markdown file, or the VTODO entries of an
+    /// iCalendar file, to the todo list
+    Import {
+        file: PathBuf,
+        /// Format of the file, guessed from its extension by default
+        #[arg(short, long)]
+        format: Option<Format>,
+        /// Marker of in progress tasks, as in `- [~]`
+        #[arg(long, default_value_t = IN_PROGRESS_MARKER)]
+        in_progress: char,
+    },
+    /// Print the todo list and the history as a Markdown checklist or an iCalendar file
+    Export {
+        /// Write to a file instead of the standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Format of the output, guessed from the extension of the file by default
+        #[arg(short, long)]
+        format: Option<Format>,
+        /// Marker of in progress tasks, as in `- [~]`
+        #[arg(long, default_value_t = IN_PROGRESS_MARKER)]
+        in_progress: char,
+    },
+    /// Sync the code topic with the TODO: and FIXME: comments of the project
+    Scan,
+    /// Move the tasks of the history completed some days ago to monthly archive files
+    Archive {
+        /// Age in days of the completed tasks to archive
+        #[arg(short, long, default_value_t = ARCHIVE_DAYS)]
+        days: u32,
+    },
+    /// Delete the archived tasks completed some days ago
+    Purge {
+        /// Age in days of the archived tasks to keep
+        #[arg(short, long, default_value_t = RETENTION_DAYS)]
+        keep_days: u32,
+    },
+    /// Print the time spent in progress on each task, the longest first
+    Report {
+        /// Only count the time spent from this day on, as YYYY-MM-DD
+        #[arg(short, long)]
+        since: Option<NaiveDate>,
+    },
+}
+
+/// File formats of the import and export commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// GitHub flavored checklist
+    Markdown,
+    /// iCalendar VTODO entries, for calendar apps
+    Ics,
+}
+
+impl Format {
+    /// Format given explicitly, else the one of the file extension, Markdown by default.
+    fn resolve(format: Option<Format>, path: Option<&Path>) -> Format {
+        let is_ics = path
+            .and_then(Path::extension)
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("ics"));
+        match format {
+            Some(format) => format,
+            None if is_ics => Format::Ics,
+            None => Format::Markdown,
+        }
+    }
+}
+
+fn load(store: &Store, file_name: &str) -> Result<Vec<TodoItem>> {
+    store
+        .load_todo_items(file_name)
+        .map_err(|err| eyre!("could not load {file_name}: {err}"))
+}
+
+/// Find the path in `items` of the task with the given id.
+fn find(items: &[TodoItem], id: u64) -> Result<ItemPath> {
+    tree::find(items, &|item| item.id == id).ok_or_else(|| eyre!("no open task with id {id}"))
+}
+
+pub fn run(command: Command, store: &Store, topic: &str) -> Result<()> {
+    // comments always go to their own topic
+    let topic = if matches!(command, Command::Scan) {
+        CODE_TOPIC
+    } else {
+        topic
+    };
+    let topic_file = utils::topic_file(topic);
+    let history_file = utils::history_file(topic);
+    let mut items = load(store, &topic_file)?;
+    let mut history = load(store, &history_file)?;
+    let mut last_id = store
+        .load_last_id(topic)
+        .map_err(|err| eyre!("could not load the last id of {topic}: {err}"))?;
+    base::assign_ids(&mut items, &mut history, &mut last_id);
+    match command {
+        Command::Add {
+            name,
+            info,
+            due,
+            priority,
+            tags,
+            parent,
+            repeat,
+        } => {
+            let mut item = TodoItem::new(Status::Todo, &name, &info);
+            item.due = due;
+            item.priority = priority;
+            item.tags = parse_tags(&tags);
+            item.recurrence = repeat;
+            item.id = base::next_id(&items, &history, last_id);
+            let id = item.id;
+            last_id = id;
+            let siblings = match parent {
+                Some(parent) => {
+                    let path = find(&items, parent)?;
+                    match tree::get_mut(&mut items, &path) {
+                        Some(parent) => &mut parent.children,
+                        None => return Err(eyre!("no open task with id {parent}")),
+                    }
+                }
+                None => &mut items,
+            };
+            siblings.push(item);
+            println!("Added task {id}: {name}");
+        }
+        Command::List { status, json } => {
+            let matches = |item: &TodoItem| status.is_none_or(|status| item.status == status);
+            if json {
+                let listed: Vec<&TodoItem> = items.iter().filter(|item| matches(item)).collect();
+                println!("{}", serde_json::to_string_pretty(&listed)?);
+            } else {
+                // subtasks are printed indented under their parent
+                for (depth, item, matching) in list_rows(&items, &matches) {
+                    let indent = "  ".repeat(depth);
+                    let id = if matching {
+                        item.id.to_string()
+                    } else {
+                        String::new()
+                    };
+                    println!("{id:>3} {indent}{}", format_item(item));
+                }
+            }
+            return Ok(());
+        }
+        Command::Done { id } => {
+            let path = find(&items, id)?;
+            if let Some(item) = tree::get_mut(&mut items, &path) {
+                item.set_status(Status::Completed);
+            }
+        }
+        Command::Rm { id } => {
+            let path = find(&items, id)?;
+            if let Some(item) = tree::remove(&mut items, &path) {
+                println!("Removed task {id}: {}", item.todo);
+            }
+        }
+        Command::Flush => {
+            let today = chrono::Local::now().date_naive();
+            (items, history) = base::flush(items, history, today);
+            base::assign_ids(&mut items, &mut history, &mut last_id);
+        }
+        Command::Import {
+            file,
+            format,
+            in_progress,
+        } => {
+            let content = fs::read_to_string(&file)
+                .map_err(|err| eyre!("could not read {}: {err}", file.display()))?;
+            let imported = match Format::resolve(format, Some(&file)) {
+                Format::Markdown => markdown::import(&content, in_progress),
+                Format::Ics => ical::import(&content),
+            };
+            let mut count = 0;
+            tree::for_each(&imported, &mut |_| count += 1);
+            println!("Imported {count} tasks from {}", file.display());
+            items.extend(imported);
+            base::assign_ids(&mut items, &mut history, &mut last_id);
+        }
+        Command::Export {
+            output,
+            format,
+            in_progress,
+        } => {
+            let exported = match Format::resolve(format, output.as_deref()) {
+                Format::Markdown => {
+                    let mut checklist = markdown::export(&items, in_progress);
+                    checklist.push_str(&markdown::export(&history, in_progress));
+                    checklist
+                }
+                Format::Ics => {
+                    let all: Vec<TodoItem> = items.iter().chain(&history).cloned().collect();
+                    ical::export(&all)
+                }
+            };
+            match output {
+                Some(path) => fs::write(&path, exported)
+                    .map_err(|err| eyre!("could not write {}: {err}", path.display()))?,
+                None => print!("{exported}"),
+            }
+            return Ok(());
+        }
+        Command::Scan => {
+            let root = store.project_root();
+            let comments = scan::scan_tree(&root);
+            scan::sync(&mut items, &comments);
+            base::assign_ids(&mut items, &mut history, &mut last_id);
+            println!("Found {} comments in {}", comments.len(), root.display());
+        }
+        Command::Archive { days } => {
+            let cutoff = Utc::now() - TimeDelta::days(days.into());
+            let count = archive::archive(store, topic, &mut history, cutoff)
+                .map_err(|err| eyre!("could not archive the history: {err}"))?;
+            println!("Archived {count} tasks");
+        }
+        Command::Purge { keep_days } => {
+            let cutoff = Utc::now() - TimeDelta::days(keep_days.into());
+            let count = archive::purge(store, topic, cutoff)
+                .map_err(|err| eyre!("could not purge the archives: {err}"))?;
+            println!("Purged {count} archived tasks");
+            return Ok(());
+        }
+        Command::Report { since } => {
+            let since = since
+                .and_then(|day| day.and_hms_opt(0, 0, 0))
+                .and_then(|start| start.and_local_timezone(Local).earliest())
+                .map(|start| start.with_timezone(&Utc));
+            report(&items, &history, since);
+            return Ok(());
+        }
+    }
+    // as in the app, a crash in between a flush duplicates the moved tasks instead of losing them
+    store
+        .save_last_id(topic, last_id)
+        .map_err(|err| eyre!("could not save the last id of {topic}: {err}"))?;
+    store.save_list(&history, &history_file)?;
+    store.save_list(&items, &topic_file)?;
+    Ok(())
+}
+
+/// Rows printed by `list`, depth first: the tasks satisfying `keep` and their parents, with their
+/// depth and whether they satisfy `keep`.
+///
+/// Unlike in the app, the subtasks of folded tasks are listed too.
+fn list_rows<'a>(
+    items: &'a [TodoItem],
+    keep: &impl Fn(&TodoItem) -> bool,
+) -> Vec<(usize, &'a TodoItem, bool)> {
+    let mut rows = Vec::new();
+    let mut stack: Vec<(usize, &TodoItem)> = items.iter().rev().map(|item| (0, item)).collect();
+    while let Some((depth, item)) = stack.pop() {
+        if !tree::any(item, keep) {
+            continue;
+        }
+        rows.push((depth, item, keep(item)));
+        stack.extend(item.children.iter().rev().map(|child| (depth + 1, child)));
+    }
+    rows
+}
+
+/// Print the time spent on the tasks of both lists, subtasks included, and the total.
+fn report(items: &[TodoItem], history: &[TodoItem], since: Option<DateTime<Utc>>) {
+    let now = Utc::now();
+    // time, id, name and whether the timer is running
+    let mut spent: Vec<(TimeDelta, u64, String, bool)> = Vec::new();
+    for list in [items, history] {
+        tree::for_each(list, &mut |item| {
+            let time = item.time_spent(since, now);
+            if time > TimeDelta::zero() {
+                let running = item.running_since().is_some();
+                spent.push((time, item.id, item.todo.clone(), running));
+            }
+        });
+    }
+    spent.sort_by_key(|(time, ..)| std::cmp::Reverse(*time));
+    let mut total = TimeDelta::zero();
+    for (time, id, name, running) in &spent {
+        total += *time;
+        let running = if *running { " (running)" } else { "" };
+        println!(
+            "{:>9} {id:>3} {name}{running}",
+            base::format_duration(*time)
+        );
+    }
+    println!("{:>9} total", base::format_duration(total));
+}
+
+fn format_item(item: &TodoItem) -> String {
+    let symbol = match item.status {
+        Status::Todo => "☐",
+        Status::InProgress => "✍",
+        Status::Completed => "✓",
+    };
+    let mut line = format!("{symbol} {}", item.todo);
+    if let Some((completed, total)) = item.progress() {
+        line.push_str(&format!(" {completed}/{total}"));
+    }
+    if let Some(priority) = item.priority {
+        line.push_str(&format!(" [{priority}]"));
+    }
+    if let Some(due) = item.due {
+        line.push_str(&format!(" due {due}"));
+    }
+    if let Some(recurrence) = &item.recurrence {
+        line.push_str(&format!(" ({recurrence})"));
+    }
+    if !item.tags.is_empty() {
+        line.push_str(&format!(" #{}", item.tags.join(" #")));
+    }
+    if !item.info.is_empty() {
+        line.push_str(&format!(" - {}", item.info));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_rows() {
+        let mut parent = TodoItem::new(Status::Todo, "parent", "");
+        parent.collapsed = true;
+        parent.children = vec![
+            TodoItem::new(Status::Completed, "done child", ""),
+            TodoItem::new(Status::Todo, "open child", ""),
+        ];
+        let items = vec![parent, TodoItem::new(Status::Todo, "other", "")];
+        let rows = |keep: &dyn Fn(&TodoItem) -> bool| -> Vec<(usize, String, bool)> {
+            list_rows(&items, &keep)
+                .into_iter()
+                .map(|(depth, item, matching)| (depth, item.todo.clone(), matching))
+                .collect()
+        };
+
+        // folded subtasks are listed
+        let all = rows(&|_| true);
+        let names: Vec<&str> = all.iter().map(|(_, name, _)| name.as_str()).collect();
+        assert_eq!(names, ["parent", "done child", "open child", "other"]);
+        assert_eq!(all[1].0, 1);
+
+        // a parent is only listed for its matching subtasks, as not matching
+        let completed = rows(&|item| item.status == Status::Completed);
+        assert_eq!(
+            completed,
+            [
+                (0, "parent".to_string(), false),
+                (1, "done child".to_string(), true)
+            ]
+        );
+    }
+}
@@ -0,0 +1,697 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::{
+    palette::tailwind::{BLUE, CYAN, GREEN, ORANGE, PURPLE, RED, SLATE, TEAL},
+    Color, Modifier, Style,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use unicode_width::UnicodeWidthStr;
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Palette used for rendering, the dark theme until `set_theme` is called.
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::dark)
+}
+
+pub fn set_theme(theme: Theme) {
+    _ = THEME.set(theme);
+}
+
+/// Location of the user configuration, `~/.config/todo/config.toml`.
+pub fn config_path() -> Option<PathBuf> {
+    Some(
+        dirs::home_dir()?
+            .join(".config")
+            .join("todo")
+            .join("config.toml"),
+    )
+}
+
+/// Everything the user can remap, from the main view and the topic panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    /// Clear the search filter, or quit when there is none.
+    Back,
+    SelectNone,
+    SelectNext,
+    SelectPrevious,
+    SelectFirst,
+    SelectLast,
+    ToggleStatus,
+    SwitchList,
+    Flush,
+    Add,
+    AddSubtask,
+    ToggleCollapse,
+    Edit,
+    Delete,
+    Sort,
+    /// Swap the selected item with the next one.
+    MoveDown,
+    /// Swap the selected item with the previous one.
+    MoveUp,
+    /// Set the selected item back to the previous status, moving its card to the left.
+    PreviousStatus,
+    /// Set the selected item to the next status, moving its card to the right.
+    NextStatus,
+    Undo,
+    Redo,
+    Topics,
+    Search,
+    FilterStatus,
+    /// Show or hide the statistics of the topic.
+    Stats,
+    /// Show the todo list as a board with a column per status, or back as a list.
+    Board,
+    /// Browse the archived tasks of the topic.
+    Archive,
+    /// Move the selected task of the history or of the archive back to the todo list.
+    Restore,
+    /// Switch to the topic selected in the panel.
+    OpenTopic,
+    NewTopic,
+    RenameTopic,
+    DeleteTopic,
+}
+
+/// Where an action applies, actions of different scopes can share keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Lists,
+    Topics,
+    /// Both the lists and the topic panel.
+    Both,
+}
+
+impl Action {
+    /// Short description shown in the footer.
+    pub const fn description(&self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::Back => "back",
+            Self::SelectNone => "unselect",
+            Self::SelectNext => "down",
+            Self::SelectPrevious => "up",
+            Self::SelectFirst => "top",
+            Self::SelectLast => "bottom",
+            Self::ToggleStatus => "status",
+            Self::SwitchList => "switch list",
+            Self::Flush => "flush",
+            Self::Add => "add",
+            Self::AddSubtask => "add subtask",
+            Self::ToggleCollapse => "fold",
+            Self::Edit => "edit",
+            Self::Delete => "delete",
+            Self::Sort => "sort",
+            Self::MoveDown => "move down",
+            Self::MoveUp => "move up",
+            Self::PreviousStatus => "previous status",
+            Self::NextStatus => "next status",
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+            Self::Topics => "topics",
+            Self::Search => "search",
+            Self::FilterStatus => "filter status",
+            Self::Stats => "stats",
+            Self::Board => "board",
+            Self::Archive => "archive",
+            Self::Restore => "restore",
+            Self::OpenTopic => "switch",
+            Self::NewTopic => "new",
+            Self::RenameTopic => "rename",
+            Self::DeleteTopic => "delete",
+        }
+    }
+
+    const fn scope(&self) -> Scope {
+        match self {
+            Self::OpenTopic | Self::NewTopic | Self::RenameTopic | Self::DeleteTopic => {
+                Scope::Topics
+            }
+            // moving in the panel, or closing it
+            Self::SelectNext
+            | Self::SelectPrevious
+            | Self::SelectFirst
+            | Self::SelectLast
+            | Self::Topics
+            | Self::Back
+            | Self::Quit => Scope::Both,
+            _ => Scope::Lists,
+        }
+    }
+}
+
+/// Screen showing its own help line in the footer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Lists,
+    Board,
+    Archive,
+    Stats,
+    /// The lists while searching.
+    Search,
+}
+
+impl View {
+    /// Actions listed in the help line, the most useful first as the line is cut to fit.
+    const fn actions(self) -> &'static [Action] {
+        use Action::*;
+        match self {
+            Self::Lists => &[
+                Add,
+                Edit,
+                ToggleStatus,
+                Delete,
+                Undo,
+                Search,
+                Topics,
+                Quit,
+                SwitchList,
+                AddSubtask,
+                ToggleCollapse,
+                Flush,
+                Redo,
+                FilterStatus,
+                Board,
+                Archive,
+                Stats,
+                Sort,
+                MoveDown,
+                MoveUp,
+                PreviousStatus,
+                NextStatus,
+                Restore,
+                SelectNext,
+                SelectPrevious,
+                SelectNone,
+                SelectFirst,
+                SelectLast,
+            ],
+            Self::Board => &[
+                SelectNone,
+                ToggleStatus,
+                PreviousStatus,
+                NextStatus,
+                Add,
+                Edit,
+                Delete,
+                Board,
+                Quit,
+                Undo,
+                Redo,
+            ],
+            Self::Archive => &[Restore, Back, Quit, SelectNext, SelectPrevious],
+            Self::Stats => &[Back, Quit],
+            Self::Search => &[Back, Search, FilterStatus, Edit, ToggleStatus, Quit],
+        }
+    }
+}
+
+impl Action {
+    /// Description in the help line of a view, where some keys do something else than in the
+    /// lists.
+    const fn description_in(&self, view: View) -> &'static str {
+        match (view, self) {
+            (View::Board, Self::SelectNone) => "left column",
+            (View::Board, Self::ToggleStatus) => "right column",
+            (View::Board, Self::PreviousStatus) => "move left",
+            (View::Board, Self::NextStatus) => "move right",
+            (View::Board, Self::Board) | (View::Archive | View::Stats, Self::Back) => "lists",
+            (View::Search, Self::Back) => "clear search",
+            _ => self.description(),
+        }
+    }
+}
+
+impl Scope {
+    const fn overlaps(self, other: Scope) -> bool {
+        !matches!(
+            (self, other),
+            (Scope::Lists, Scope::Topics) | (Scope::Topics, Scope::Lists)
+        )
+    }
+}
+
+/// A key with its modifiers, written as `q`, `Ctrl-r`, `Alt-Enter`, `Up` or `Space`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Shift is not compared, as it is already part of upper case characters.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        let modifiers = event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        self.code == event.code && self.modifiers == modifiers
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        let has_prefix = |rest: &str, prefix: &str| {
+            rest.len() > prefix.len()
+                && rest
+                    .get(..prefix.len())
+                    .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+        };
+        loop {
+            if has_prefix(rest, "ctrl-") {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if has_prefix(rest, "alt-") {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "esc" => KeyCode::Esc,
+                "enter" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                other => match other.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) => KeyCode::F(n),
+                    None => return Err(format!("unknown key '{s}'")),
+                },
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// Keys bound to each action, in footer order.
+#[derive(Debug)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<Key>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = [
+            (Action::SelectNext, &["Down", "j"][..]),
+            (Action::SelectPrevious, &["Up", "k"]),
+            (Action::SelectNone, &["Left", "h"]),
+            (Action::ToggleStatus, &["Right", "l", "Enter"]),
+            (Action::SelectFirst, &["g", "Home"]),
+            (Action::SelectLast, &["G", "End"]),
+            (Action::SwitchList, &["w"]),
+            (Action::Add, &["a"]),
+            (Action::AddSubtask, &["A"]),
+            (Action::ToggleCollapse, &["Space"]),
+            (Action::Edit, &["e"]),
+            (Action::Delete, &["d"]),
+            (Action::Flush, &["f"]),
+            (Action::Sort, &["s"]),
+            (Action::MoveDown, &["J"]),
+            (Action::MoveUp, &["K"]),
+            (Action::PreviousStatus, &["H"]),
+            (Action::NextStatus, &["L"]),
+            (Action::Undo, &["u"]),
+            (Action::Redo, &["Ctrl-r"]),
+            (Action::Topics, &["t"]),
+            (Action::Search, &["/"]),
+            (Action::FilterStatus, &["v"]),
+            (Action::Stats, &["S"]),
+            (Action::Board, &["b"]),
+            (Action::Archive, &["Z"]),
+            (Action::Restore, &["r"]),
+            (Action::OpenTopic, &["Enter"]),
+            (Action::NewTopic, &["n"]),
+            (Action::RenameTopic, &["r"]),
+            (Action::DeleteTopic, &["d"]),
+            (Action::Back, &["Esc"]),
+            (Action::Quit, &["q"]),
+        ];
+        Self {
+            bindings: bindings
+                .into_iter()
+                .map(|(action, keys)| {
+                    let keys = keys.iter().map(|key| key.parse().unwrap()).collect();
+                    (action, keys)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Action bound to a key in the lists.
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        self.find(event, Scope::Lists)
+    }
+
+    /// Action bound to a key in the topic panel.
+    pub fn topic_action(&self, event: &KeyEvent) -> Option<Action> {
+        self.find(event, Scope::Topics)
+    }
+
+    fn find(&self, event: &KeyEvent, scope: Scope) -> Option<Action> {
+        self.bindings
+            .iter()
+            .filter(|(action, _)| action.scope().overlaps(scope))
+            .find(|(_, keys)| keys.iter().any(|key| key.matches(event)))
+            .map(|(action, _)| *action)
+    }
+
+    /// Replace the keys of the actions listed in the configuration.
+    fn remap(&mut self, keys: &HashMap<Action, Vec<String>>) -> Result<(), String> {
+        for (action, bound) in self.bindings.iter_mut() {
+            if let Some(names) = keys.get(action) {
+                *bound = names
+                    .iter()
+                    .map(|name| name.parse())
+                    .collect::<Result<_, _>>()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fail when a key triggers more than one action in the same view.
+    fn validate(&self) -> Result<(), String> {
+        let mut seen: HashMap<Key, Vec<Action>> = HashMap::new();
+        for (action, keys) in &self.bindings {
+            for key in keys {
+                let actions = seen.entry(*key).or_default();
+                if let Some(other) = actions
+                    .iter()
+                    .find(|other| other.scope().overlaps(action.scope()))
+                {
+                    return Err(format!(
+                        "key '{key}' is bound to both {other:?} and {action:?}"
+                    ));
+                }
+                actions.push(*action);
+            }
+        }
+        Ok(())
+    }
+
+    /// Help of a view, listing the first key of its actions as long as whole entries fit in
+    /// `rows` lines of `width` columns.
+    pub fn help(&self, view: View, width: usize, rows: usize) -> Vec<String> {
+        let entries = view.actions().iter().filter_map(|action| {
+            let (_, keys) = self.bindings.iter().find(|(bound, _)| bound == action)?;
+            Some(format!("{} {}", keys.first()?, action.description_in(view)))
+        });
+        let mut lines: Vec<String> = Vec::new();
+        for entry in entries {
+            let fits = |line: &String| line.width() + 2 + entry.width() <= width;
+            if let Some(line) = lines.last_mut().filter(|line| fits(line)) {
+                line.push_str(", ");
+                line.push_str(&entry);
+            } else if lines.len() < rows && entry.width() <= width {
+                lines.push(entry);
+            } else {
+                break;
+            }
+        }
+        lines
+    }
+
+    /// Help line of the topic panel.
+    pub fn topic_help(&self) -> String {
+        self.help_of(|action| action.scope() == Scope::Topics || *action == Action::Back)
+    }
+
+    fn help_of(&self, shown: impl Fn(&Action) -> bool) -> String {
+        self.bindings
+            .iter()
+            .filter(|(action, _)| shown(action))
+            .filter_map(|(action, keys)| {
+                Some(format!("{} {}", keys.first()?, action.description()))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Colors of the interface.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub header_fg: Color,
+    pub header_bg: Color,
+    pub row_bg: Color,
+    pub alt_row_bg: Color,
+    pub selected_bg: Color,
+    pub text_fg: Color,
+    pub completed_fg: Color,
+    pub in_progress_fg: Color,
+    pub overdue_fg: Color,
+    pub error_fg: Color,
+    pub muted_fg: Color,
+    pub tag_bg: Vec<Color>,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            header_fg: SLATE.c100,
+            header_bg: BLUE.c800,
+            row_bg: SLATE.c950,
+            alt_row_bg: SLATE.c900,
+            selected_bg: SLATE.c800,
+            text_fg: SLATE.c200,
+            completed_fg: GREEN.c500,
+            in_progress_fg: ORANGE.c300,
+            overdue_fg: RED.c400,
+            error_fg: RED.c500,
+            muted_fg: SLATE.c400,
+            tag_bg: vec![BLUE.c700, PURPLE.c700, TEAL.c700, CYAN.c800],
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            header_fg: SLATE.c50,
+            header_bg: BLUE.c600,
+            row_bg: SLATE.c50,
+            alt_row_bg: SLATE.c100,
+            selected_bg: SLATE.c300,
+            text_fg: SLATE.c900,
+            completed_fg: GREEN.c700,
+            in_progress_fg: ORANGE.c700,
+            overdue_fg: RED.c600,
+            error_fg: RED.c700,
+            muted_fg: SLATE.c500,
+            tag_bg: vec![BLUE.c200, PURPLE.c200, TEAL.c200, CYAN.c200],
+        }
+    }
+
+    pub fn header_style(&self) -> Style {
+        Style::new().fg(self.header_fg).bg(self.header_bg)
+    }
+
+    pub fn selected_style(&self) -> Style {
+        Style::new()
+            .bg(self.selected_bg)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Override the colors set in the configuration.
+    fn apply(&mut self, colors: &ColorOverrides) -> Result<(), String> {
+        let parse = |value: &Option<String>, color: &mut Color| -> Result<(), String> {
+            if let Some(value) = value {
+                *color = value
+                    .parse()
+                    .map_err(|_| format!("invalid color '{value}'"))?;
+            }
+            Ok(())
+        };
+        parse(&colors.header_fg, &mut self.header_fg)?;
+        parse(&colors.header_bg, &mut self.header_bg)?;
+        parse(&colors.row_bg, &mut self.row_bg)?;
+        parse(&colors.alt_row_bg, &mut self.alt_row_bg)?;
+        parse(&colors.selected_bg, &mut self.selected_bg)?;
+        parse(&colors.text_fg, &mut self.text_fg)?;
+        parse(&colors.completed_fg, &mut self.completed_fg)?;
+        parse(&colors.in_progress_fg, &mut self.in_progress_fg)?;
+        parse(&colors.overdue_fg, &mut self.overdue_fg)?;
+        parse(&colors.error_fg, &mut self.error_fg)?;
+        parse(&colors.muted_fg, &mut self.muted_fg)?;
+        if let Some(tag_bg) = &colors.tag_bg {
+            self.tag_bg = tag_bg
+                .iter()
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid color '{value}'"))
+                })
+                .collect::<Result<_, _>>()?;
+            if self.tag_bg.is_empty() {
+                return Err("tag_bg needs at least one color".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Colors written as names (`red`), indexes (`42`) or hex codes (`#1e293b`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColorOverrides {
+    header_fg: Option<String>,
+    header_bg: Option<String>,
+    row_bg: Option<String>,
+    alt_row_bg: Option<String>,
+    selected_bg: Option<String>,
+    text_fg: Option<String>,
+    completed_fg: Option<String>,
+    in_progress_fg: Option<String>,
+    overdue_fg: Option<String>,
+    error_fg: Option<String>,
+    muted_fg: Option<String>,
+    tag_bg: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    /// Built-in theme, `dark` or `light`.
+    theme: Option<String>,
+    keys: HashMap<Action, Vec<String>>,
+    colors: ColorOverrides,
+}
+
+/// Load the configuration file, falling back on the defaults when there is none.
+pub fn load() -> Result<(Keymap, Theme), String> {
+    let Some(path) = config_path().filter(|path| path.exists()) else {
+        return Ok((Keymap::default(), Theme::dark()));
+    };
+    let content = fs::read_to_string(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+    parse(&content).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+fn parse(content: &str) -> Result<(Keymap, Theme), String> {
+    let config: ConfigFile = toml::from_str(content).map_err(|err| err.to_string())?;
+    let mut theme = match config.theme.as_deref() {
+        None | Some("dark") => Theme::dark(),
+        Some("light") => Theme::light(),
+        Some(other) => return Err(format!("unknown theme '{other}', expected dark or light")),
+    };
+    theme.apply(&config.colors)?;
+    let mut keymap = Keymap::default();
+    keymap.remap(&config.keys)?;
+    keymap.validate()?;
+    Ok((keymap, theme))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let (keymap, theme) = parse(
+            r##"
+            theme = "light"
+            [keys]
+            quit = ["Ctrl-q"]
+            undo = ["z", "u"]
+            [colors]
+            text_fg = "#102030"
+            "##,
+        )
+        .unwrap();
+        let ctrl_q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL);
+        let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        let shift_a = KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        assert_eq!(keymap.action(&ctrl_q), Some(Action::Quit));
+        assert_eq!(keymap.action(&q), None);
+        assert_eq!(keymap.action(&shift_a), Some(Action::AddSubtask));
+        assert!(keymap.help(View::Lists, 200, 1)[0].contains("z undo"));
+        // the topic panel has its own actions, sharing keys with the lists
+        let d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(&d), Some(Action::Delete));
+        assert_eq!(keymap.topic_action(&d), Some(Action::DeleteTopic));
+        assert_eq!(keymap.topic_action(&ctrl_q), Some(Action::Quit));
+        assert!(!keymap.help(View::Lists, 200, 1)[0].contains("rename"));
+        assert_eq!(
+            keymap.topic_help(),
+            "Enter switch, n new, r rename, d delete, Esc back"
+        );
+        assert_eq!(theme.text_fg, Color::Rgb(0x10, 0x20, 0x30));
+        assert_eq!(theme.row_bg, Theme::light().row_bg);
+
+        let conflict = parse("[keys]\ndelete = [\"a\"]").unwrap_err();
+        assert_eq!(conflict, "key 'a' is bound to both Add and Delete");
+        let conflict = parse("[keys]\nnew_topic = [\"j\"]").unwrap_err();
+        assert_eq!(conflict, "key 'j' is bound to both SelectNext and NewTopic");
+        assert!(parse("[keys]\nquit = [\"Hyper-q\"]").is_err());
+        assert!(parse("theme = \"blue\"").is_err());
+    }
+
+    #[test]
+    fn test_help_fits() {
+        let keymap = Keymap::default();
+        let help = keymap.help(View::Lists, 60, 2);
+        assert_eq!(
+            help,
+            [
+                "a add, e edit, → status, d delete, u undo, / search",
+                "t topics, q quit, w switch list, A add subtask, Space fold",
+            ]
+        );
+        assert!(help.iter().all(|line| line.width() <= 60));
+        // the entries that do not fit are left out rather than cut
+        assert_eq!(keymap.help(View::Lists, 20, 1), ["a add, e edit"]);
+        assert!(keymap.help(View::Lists, 3, 2).is_empty());
+        assert_eq!(
+            keymap.help(View::Board, 60, 2)[0],
+            "← left column, → right column, H move left, L move right"
+        );
+        assert_eq!(
+            keymap.help(View::Archive, 60, 2),
+            ["r restore, Esc lists, q quit, ↓ down, ↑ up"]
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::base::{Status, TodoItem};
+use crate::form::StringField;
+
+/// Search query and status quick filter applied to both lists.
+pub struct Filter {
+    pub query: StringField,
+    pub status: Option<Status>,
+    /// Whether key presses are currently typed into the query.
+    pub typing: bool,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self {
+            query: StringField::new("Search"),
+            status: None,
+            typing: false,
+        }
+    }
+}
+
+impl Filter {
+    pub fn is_active(&self) -> bool {
+        !self.query.value().is_empty() || self.status.is_some()
+    }
+
+    pub fn matches(&self, item: &TodoItem) -> bool {
+        let query = self.query.value().trim();
+        self.status.is_none_or(|status| item.status == status)
+            && (fuzzy_match(query, &item.todo) || fuzzy_match(query, &item.info))
+    }
+
+    /// Round-robin over the status quick filters, starting from no filter.
+    pub fn cycle_status(&mut self) {
+        self.status = match self.status {
+            None => Some(Status::Todo),
+            Some(Status::Todo) => Some(Status::InProgress),
+            Some(Status::InProgress) => Some(Status::Completed),
+            Some(Status::Completed) => None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.query.set_value("");
+        self.status = None;
+        self.typing = false;
+    }
+}
+
+/// Case-insensitive subsequence match: every character of `pattern` appears in `text`, in order.
+pub fn fuzzy_match(pattern: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    pattern
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .all(|p| text.any(|c| c == p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert!(fuzzy_match("", "anything"));
+        assert!(fuzzy_match("wrt", "Write tests"));
+        assert!(fuzzy_match("WRITE tests", "write tests"));
+        // the characters must appear in order
+        assert!(!fuzzy_match("tw", "write"));
+        assert!(!fuzzy_match("writes", "write"));
+        assert!(fuzzy_match("été", "ÉTÉ"));
+    }
+}
@@ -1,14 +1,22 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use chrono::{NaiveDate, Utc};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Layout, Rect},
+    layout::{Constraint, Layout, Position, Rect},
     style::Stylize,
     text::Line,
-    widgets::Widget,
+    widgets::{Paragraph, Widget},
 };
 use serde::Serialize;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
-use crate::base::{Status, TodoItem};
+use crate::base::{parse_tags, Priority, Status, TodoItem};
+use crate::config::theme;
+use crate::recurrence::Recurrence;
+
+/// Date format expected in the due date field.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
 
 #[derive(Serialize)]
 pub struct TodoForm {
@@ -16,6 +24,10 @@ pub struct TodoForm {
     pub focus: Focus,
     name: StringField,
     description: StringField,
+    due: StringField,
+    repeat: StringField,
+    priority: StringField,
+    tags: StringField,
 }
 
 impl Default for TodoForm {
@@ -23,7 +35,11 @@ impl Default for TodoForm {
         Self {
             focus: Focus::Name,
             name: StringField::new("Name"),
-            description: StringField::new("Description"),
+            description: StringField::multiline("Description"),
+            due: StringField::new("Due (YYYY-MM-DD)"),
+            repeat: StringField::new("Repeat (weekly, every 2 days, cron)"),
+            priority: StringField::new("Priority (P0-P3)"),
+            tags: StringField::new("Tags (a, b)"),
         }
     }
 }
@@ -36,34 +52,180 @@ impl TodoForm {
         }
         match event.code {
             KeyCode::Tab => self.focus = self.focus.next(),
-            _ => match self.focus {
-                Focus::Name => self.name.on_key_press(event),
-                Focus::Description => self.description.on_key_press(event),
-            },
+            _ => self.focused_field_mut().on_key_press(event),
+        }
+    }
+
+    /// Insert pasted text in the focused field.
+    pub fn on_paste(&mut self, text: &str) {
+        self.focused_field_mut().on_paste(text);
+    }
+
+    fn focused_field(&self) -> &StringField {
+        match self.focus {
+            Focus::Name => &self.name,
+            Focus::Description => &self.description,
+            Focus::Due => &self.due,
+            Focus::Repeat => &self.repeat,
+            Focus::Priority => &self.priority,
+            Focus::Tags => &self.tags,
+        }
+    }
+
+    fn focused_field_mut(&mut self) -> &mut StringField {
+        match self.focus {
+            Focus::Name => &mut self.name,
+            Focus::Description => &mut self.description,
+            Focus::Due => &mut self.due,
+            Focus::Repeat => &mut self.repeat,
+            Focus::Priority => &mut self.priority,
+            Focus::Tags => &mut self.tags,
         }
     }
 
+    fn areas(&self, area: Rect) -> [Rect; 6] {
+        Layout::vertical(Constraint::from_lengths([
+            self.name.height(),
+            self.description.height(),
+            self.due.height(),
+            self.repeat.height(),
+            self.priority.height(),
+            self.tags.height(),
+        ]))
+        .areas(area)
+    }
+
     /// Render the form with the current focus.
-    ///
-    /// The cursor is placed at the end of the focused field.
     pub fn render(&self, area: Rect, buf: &mut Buffer) {
-        let [name_area, description_area] =
-            Layout::vertical(Constraint::from_lengths([1, 1])).areas(area);
+        let [name_area, description_area, due_area, repeat_area, priority_area, tags_area] =
+            self.areas(area);
 
         self.name.render(name_area, buf);
         self.description.render(description_area, buf);
+        self.due.render(due_area, buf);
+        if self.parse_due().is_err() {
+            buf.set_style(due_area, theme().error_fg);
+        }
+        self.repeat.render(repeat_area, buf);
+        if self.parse_repeat().is_err() {
+            buf.set_style(repeat_area, theme().error_fg);
+        }
+        self.priority.render(priority_area, buf);
+        if self.parse_priority().is_err() {
+            buf.set_style(priority_area, theme().error_fg);
+        }
+        self.tags.render(tags_area, buf);
     }
 
-    pub fn extract(&mut self) -> TodoItem {
-        let todo = TodoItem {
-            todo: self.name.value.to_string(),
-            info: self.description.value.to_string(),
-            status: Status::Todo,
+    /// Position of the terminal cursor, inside the focused field.
+    pub fn cursor_position(&self, area: Rect) -> Position {
+        let [name_area, description_area, due_area, repeat_area, priority_area, tags_area] =
+            self.areas(area);
+        let field_area = match self.focus {
+            Focus::Name => name_area,
+            Focus::Description => description_area,
+            Focus::Due => due_area,
+            Focus::Repeat => repeat_area,
+            Focus::Priority => priority_area,
+            Focus::Tags => tags_area,
         };
-        self.name.value = "".to_string();
-        self.description.value = "".to_string();
+        self.focused_field().cursor_position(field_area)
+    }
+
+    /// Focus the field at `position`, for a click in the form rendered in `area`.
+    pub fn focus_at(&mut self, area: Rect, position: Position) {
+        let [name_area, description_area, due_area, repeat_area, priority_area, tags_area] =
+            self.areas(area);
+        let fields = [
+            (name_area, Focus::Name),
+            (description_area, Focus::Description),
+            (due_area, Focus::Due),
+            (repeat_area, Focus::Repeat),
+            (priority_area, Focus::Priority),
+            (tags_area, Focus::Tags),
+        ];
+        if let Some((_, focus)) = fields.into_iter().find(|(area, _)| area.contains(position)) {
+            self.focus = focus;
+        }
+    }
+
+    /// Whether the optional fields hold values that can be parsed.
+    pub fn is_valid(&self) -> bool {
+        self.parse_due().is_ok() && self.parse_repeat().is_ok() && self.parse_priority().is_ok()
+    }
+
+    fn parse_due(&self) -> Result<Option<NaiveDate>, chrono::ParseError> {
+        let value = self.due.value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        NaiveDate::parse_from_str(value, DATE_FORMAT).map(Some)
+    }
+
+    fn parse_repeat(&self) -> Result<Option<Recurrence>, String> {
+        let value = self.repeat.value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        value.parse().map(Some)
+    }
+
+    fn parse_priority(&self) -> Result<Option<Priority>, String> {
+        let value = self.priority.value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        value.parse().map(Some)
+    }
+
+    pub fn extract(&mut self) -> TodoItem {
+        let mut todo = TodoItem::new(Status::Todo, "", "");
+        self.apply(&mut todo);
         todo
     }
+
+    /// Prefill the fields with an existing item, to edit it.
+    pub fn load(&mut self, item: &TodoItem) {
+        self.name.set_value(item.todo.clone());
+        self.description.set_value(item.info.clone());
+        self.due.set_value(
+            item.due
+                .map(|due| due.format(DATE_FORMAT).to_string())
+                .unwrap_or_default(),
+        );
+        self.repeat.set_value(
+            item.recurrence
+                .as_ref()
+                .map(|recurrence| recurrence.to_string())
+                .unwrap_or_default(),
+        );
+        self.priority
+            .set_value(item.priority.map(|p| p.to_string()).unwrap_or_default());
+        self.tags.set_value(item.tags.join(", "));
+        self.focus = Focus::Name;
+    }
+
+    /// Write the form values into an item, keeping its status, and clear the form.
+    pub fn apply(&mut self, item: &mut TodoItem) {
+        item.todo = self.name.value.to_string();
+        item.info = self.description.value.to_string();
+        item.due = self.parse_due().unwrap_or_default();
+        item.recurrence = self.parse_repeat().unwrap_or_default();
+        item.priority = self.parse_priority().unwrap_or_default();
+        item.tags = parse_tags(&self.tags.value);
+        item.updated_at = Utc::now();
+        self.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.name.set_value("");
+        self.description.set_value("");
+        self.due.set_value("");
+        self.repeat.set_value("");
+        self.priority.set_value("");
+        self.tags.set_value("");
+        self.focus = Focus::Name;
+    }
 }
 
 #[derive(Default, PartialEq, Eq)]
@@ -71,6 +233,10 @@ pub enum Focus {
     #[default]
     Name,
     Description,
+    Due,
+    Repeat,
+    Priority,
+    Tags,
 }
 
 impl Focus {
@@ -78,48 +244,338 @@ impl Focus {
     const fn next(&self) -> Self {
         match self {
             Self::Name => Self::Description,
-            Self::Description => Self::Name,
+            Self::Description => Self::Due,
+            Self::Due => Self::Repeat,
+            Self::Repeat => Self::Priority,
+            Self::Priority => Self::Tags,
+            Self::Tags => Self::Name,
         }
     }
 }
 
 /// A new-type representing a string field with a label.
+///
+/// The cursor is kept as a byte offset on a grapheme boundary of `value`.
 #[derive(Debug, Serialize)]
-struct StringField {
+pub struct StringField {
     #[serde(skip)]
     label: &'static str,
     value: String,
+    #[serde(skip)]
+    cursor: usize,
+    #[serde(skip)]
+    multiline: bool,
 }
 
 impl StringField {
-    const fn new(label: &'static str) -> Self {
+    pub const fn new(label: &'static str) -> Self {
+        Self {
+            label,
+            value: String::new(),
+            cursor: 0,
+            multiline: false,
+        }
+    }
+
+    /// A field accepting newlines, inserted with Shift-Enter or Alt-Enter.
+    pub const fn multiline(label: &'static str) -> Self {
         Self {
             label,
             value: String::new(),
+            cursor: 0,
+            multiline: true,
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Replace the content and move the cursor to the end.
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.len();
+    }
+
+    /// Number of rows used to render the field.
+    pub const fn height(&self) -> u16 {
+        if self.multiline {
+            3
+        } else {
+            1
         }
     }
 
     /// Handle input events for the string input.
-    fn on_key_press(&mut self, event: KeyEvent) {
+    pub fn on_key_press(&mut self, event: KeyEvent) {
+        let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
         match event.code {
-            KeyCode::Char(c) => self.value.push(c),
+            KeyCode::Char('w') if ctrl => self.delete_word(),
+            KeyCode::Char('u') if ctrl => self.delete_line(),
+            KeyCode::Char(_) if ctrl => {}
+            KeyCode::Char(c) => self.insert(&c.to_string()),
+            KeyCode::Enter
+                if self.multiline
+                    && event
+                        .modifiers
+                        .intersects(KeyModifiers::SHIFT | KeyModifiers::ALT) =>
+            {
+                self.insert("\n")
+            }
             KeyCode::Backspace => {
-                self.value.pop();
+                let start = self.previous_boundary();
+                self.value.replace_range(start..self.cursor, "");
+                self.cursor = start;
             }
+            KeyCode::Delete => {
+                let end = self.next_boundary();
+                self.value.replace_range(self.cursor..end, "");
+            }
+            KeyCode::Left if ctrl => self.cursor = self.word_start(),
+            KeyCode::Right if ctrl => self.cursor = self.word_end(),
+            KeyCode::Left => self.cursor = self.previous_boundary(),
+            KeyCode::Right => self.cursor = self.next_boundary(),
+            KeyCode::Up if self.multiline => self.move_line(-1),
+            KeyCode::Down if self.multiline => self.move_line(1),
+            KeyCode::Home => self.cursor = self.line_start(),
+            KeyCode::End => self.cursor = self.line_end(),
             _ => {}
         }
     }
-}
 
-impl Widget for &StringField {
-    fn render(self, area: Rect, buf: &mut Buffer) {
+    /// Insert pasted text at the cursor, flattening newlines in single line fields.
+    pub fn on_paste(&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n");
+        if self.multiline {
+            self.insert(&text);
+        } else {
+            self.insert(&text.replace('\n', " "));
+        }
+    }
+
+    fn insert(&mut self, text: &str) {
+        self.value.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    fn previous_boundary(&self) -> usize {
+        self.value[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.value[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |g| self.cursor + g.len())
+    }
+
+    fn line_start(&self) -> usize {
+        self.value[..self.cursor].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self) -> usize {
+        self.value[self.cursor..]
+            .find('\n')
+            .map_or(self.value.len(), |i| self.cursor + i)
+    }
+
+    /// Start of the word before the cursor, skipping the whitespace in between.
+    fn word_start(&self) -> usize {
+        let before = self.value[..self.cursor].trim_end();
+        before
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8())
+    }
+
+    /// End of the word after the cursor, skipping the whitespace in between.
+    fn word_end(&self) -> usize {
+        let after = &self.value[self.cursor..];
+        let word = after.len() - after.trim_start().len();
+        after[word..]
+            .char_indices()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(self.value.len(), |(i, _)| self.cursor + word + i)
+    }
+
+    /// Move the cursor to the line above or below, at the same column when the line is long
+    /// enough.
+    fn move_line(&mut self, offset: isize) {
+        let start = self.line_start();
+        let column = self.value[start..self.cursor].width();
+        let target = if offset < 0 {
+            let Some(previous_end) = start.checked_sub(1) else {
+                return;
+            };
+            self.value[..previous_end].rfind('\n').map_or(0, |i| i + 1)
+        } else {
+            let end = self.line_end();
+            if end == self.value.len() {
+                return;
+            }
+            end + 1
+        };
+        let line = self.value[target..].split('\n').next().unwrap_or_default();
+        let mut width = 0;
+        self.cursor = target;
+        for grapheme in line.graphemes(true) {
+            width += grapheme.width();
+            if width > column {
+                break;
+            }
+            self.cursor += grapheme.len();
+        }
+    }
+
+    /// Delete the word before the cursor, along with the whitespace following it.
+    fn delete_word(&mut self) {
+        let start = self.word_start();
+        self.value.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    /// Delete from the start of the current line up to the cursor.
+    fn delete_line(&mut self) {
+        let start = self.line_start();
+        self.value.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    fn areas(&self, area: Rect) -> [Rect; 2] {
         let constraints = [
             Constraint::Length(self.label.len() as u16 + 5),
             Constraint::Fill(1),
         ];
-        let [label_area, value_area] = Layout::horizontal(constraints).areas(area);
+        Layout::horizontal(constraints).areas(area)
+    }
+
+    /// Row and column of the cursor within the value.
+    fn cursor_offset(&self) -> (u16, u16) {
+        let before = &self.value[..self.cursor];
+        let row = before.matches('\n').count();
+        let col = before[self.line_start()..].width();
+        (row as u16, col as u16)
+    }
+
+    /// Scroll needed to keep the cursor visible in the value area.
+    fn scroll(&self, value_area: Rect) -> (u16, u16) {
+        let (row, col) = self.cursor_offset();
+        (
+            (row + 1).saturating_sub(value_area.height),
+            (col + 1).saturating_sub(value_area.width),
+        )
+    }
+
+    /// Position of the terminal cursor when the field is rendered in `area`.
+    pub fn cursor_position(&self, area: Rect) -> Position {
+        let [_, value_area] = self.areas(area);
+        let (row, col) = self.cursor_offset();
+        let (scroll_y, scroll_x) = self.scroll(value_area);
+        Position::new(
+            value_area.x + col.saturating_sub(scroll_x),
+            value_area.y + row.saturating_sub(scroll_y),
+        )
+    }
+}
+
+impl Widget for &StringField {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [label_area, value_area] = self.areas(area);
         let label = Line::from_iter(["   ", self.label, ": "]).bold();
         label.render(label_area, buf);
-        self.value.clone().render(value_area, buf);
+        Paragraph::new(self.value.as_str())
+            .scroll(self.scroll(value_area))
+            .render(value_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(field: &mut StringField, code: KeyCode, modifiers: KeyModifiers) {
+        field.on_key_press(KeyEvent::new(code, modifiers));
+    }
+
+    #[test]
+    fn test_edit_graphemes() {
+        let mut field = StringField::new("Name");
+        // a combining accent and a family emoji joined by ZWJ are single graphemes
+        field.set_value("cafe\u{301} 👨‍👩‍👧!");
+        press(&mut field, KeyCode::Left, KeyModifiers::NONE);
+        press(&mut field, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(field.value(), "cafe\u{301} !");
+        press(&mut field, KeyCode::Left, KeyModifiers::NONE);
+        press(&mut field, KeyCode::Left, KeyModifiers::NONE);
+        press(&mut field, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(field.value(), "caf !");
+        press(&mut field, KeyCode::Char('é'), KeyModifiers::NONE);
+        assert_eq!(field.value(), "café !");
+        assert_eq!(field.cursor_offset(), (0, 4));
+    }
+
+    #[test]
+    fn test_word_motions() {
+        let mut field = StringField::new("Name");
+        field.set_value("one two  three ");
+        press(&mut field, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(field.value(), "one two  ");
+        press(&mut field, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(field.value(), "one ");
+
+        field.set_value("one two  three");
+        press(&mut field, KeyCode::Home, KeyModifiers::NONE);
+        press(&mut field, KeyCode::Right, KeyModifiers::CONTROL);
+        assert_eq!(field.cursor, 3);
+        press(&mut field, KeyCode::Right, KeyModifiers::CONTROL);
+        assert_eq!(field.cursor, 7);
+        press(&mut field, KeyCode::Right, KeyModifiers::CONTROL);
+        assert_eq!(field.cursor, 14);
+        press(&mut field, KeyCode::Left, KeyModifiers::CONTROL);
+        assert_eq!(field.cursor, 9);
+        press(&mut field, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!(field.value(), "three");
+    }
+
+    #[test]
+    fn test_multiline_motions() {
+        let mut field = StringField::multiline("Description");
+        field.set_value("first line\nab\nthird");
+        press(&mut field, KeyCode::Home, KeyModifiers::NONE);
+        assert_eq!(field.cursor_offset(), (2, 0));
+        press(&mut field, KeyCode::Up, KeyModifiers::NONE);
+        press(&mut field, KeyCode::End, KeyModifiers::NONE);
+        assert_eq!(field.cursor_offset(), (1, 2));
+        // the column is kept when the line is long enough, else clamped to its end
+        press(&mut field, KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(field.cursor_offset(), (0, 2));
+        press(&mut field, KeyCode::End, KeyModifiers::NONE);
+        press(&mut field, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(field.cursor_offset(), (1, 2));
+        press(&mut field, KeyCode::Down, KeyModifiers::NONE);
+        press(&mut field, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(field.cursor_offset(), (2, 2));
+        press(&mut field, KeyCode::Char('u'), KeyModifiers::CONTROL);
+        assert_eq!(field.value(), "first line\nab\nird");
+
+        let mut field = StringField::new("Name");
+        field.set_value("single");
+        press(&mut field, KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(field.cursor_offset(), (0, 6));
+    }
+
+    #[test]
+    fn test_cursor_scrolls_into_view() {
+        let mut field = StringField::new("Tag");
+        field.set_value("a long value");
+        // the label takes 8 columns, leaving 4 for the value
+        let area = Rect::new(0, 0, 12, 1);
+        assert_eq!(field.cursor_position(area), Position::new(11, 0));
+        press(&mut field, KeyCode::Home, KeyModifiers::NONE);
+        assert_eq!(field.cursor_position(area), Position::new(8, 0));
     }
 }
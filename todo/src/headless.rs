@@ -0,0 +1,262 @@
+//! Drives the app with key presses against a temporary store, checking the saved files and the
+//! rendered screen.
+
+use std::fs;
+
+use ratatui::{
+    backend::TestBackend,
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
+    style::Modifier,
+    Terminal,
+};
+use serde_json::Value;
+
+use crate::base::{Status, TodoItem};
+use crate::config::theme;
+use crate::utils::{self, TempFolder};
+use crate::App;
+
+const WIDTH: u16 = 60;
+const HEIGHT: u16 = 18;
+
+struct Harness {
+    app: App,
+    terminal: Terminal<TestBackend>,
+    dir: TempFolder,
+}
+
+impl Harness {
+    /// App opened on the default topic of an empty `.todos` folder, unique to the test.
+    fn new(name: &str) -> Self {
+        let dir = TempFolder::new(name);
+        Self {
+            app: App::new(dir.store(), utils::DEFAULT_TOPIC),
+            terminal: Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap(),
+            dir,
+        }
+    }
+
+    /// Send each key as the event loop does, saving after every mutation.
+    fn press(&mut self, keys: &[KeyCode]) {
+        for key in keys {
+            self.app
+                .handle_event(Event::Key(KeyEvent::new(*key, KeyModifiers::NONE)));
+            if self.app.dirty {
+                self.app.save();
+            }
+        }
+    }
+
+    fn type_text(&mut self, text: &str) {
+        let keys: Vec<KeyCode> = text.chars().map(KeyCode::Char).collect();
+        self.press(&keys);
+    }
+
+    /// Content of a file of the store, parsed.
+    fn file(&self, name: &str) -> Value {
+        let content = fs::read_to_string(self.dir.store().root().join(name)).unwrap();
+        serde_json::from_str(&content).unwrap()
+    }
+
+    /// Values of a field for each task of a file of the store.
+    fn field(&self, name: &str, field: &str) -> Vec<Value> {
+        let file = self.file(name);
+        let tasks = file.as_array().unwrap();
+        tasks.iter().map(|task| task[field].clone()).collect()
+    }
+
+    /// Values of a field for each task of the todo list, as saved.
+    fn todo(&self, field: &str) -> Vec<Value> {
+        self.field(&utils::topic_file(utils::DEFAULT_TOPIC), field)
+    }
+
+    /// Values of a field for each task of the history, as saved.
+    fn history(&self, field: &str) -> Vec<Value> {
+        self.field(&utils::history_file(utils::DEFAULT_TOPIC), field)
+    }
+
+    /// Render the app and return the text of each row, trailing spaces trimmed.
+    fn screen(&mut self) -> Vec<String> {
+        self.terminal
+            .draw(|frame| frame.render_widget(&mut self.app, frame.area()))
+            .unwrap();
+        let buffer = self.terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                let row: String = (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect();
+                row.trim_end().to_string()
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_add_toggle_flush_delete() {
+    let mut harness = Harness::new("headless");
+
+    harness.press(&[KeyCode::Char('a')]);
+    harness.type_text("Write tests");
+    harness.press(&[KeyCode::Enter]);
+    assert_eq!(harness.todo("todo"), ["Create a task", "Write tests"]);
+
+    // start then complete the new task
+    harness.press(&[KeyCode::Char('j'), KeyCode::Char('j')]);
+    harness.press(&[KeyCode::Char('l')]);
+    assert_eq!(harness.todo("status"), ["TODO", "INPROGRESS"]);
+    harness.press(&[KeyCode::Char('l')]);
+    harness.press(&[KeyCode::Char('f'), KeyCode::Char('y')]);
+    assert_eq!(harness.todo("todo"), ["Create a task"]);
+    assert_eq!(harness.history("todo"), ["Write tests"]);
+    assert_eq!(harness.history("status"), ["COMPLETED"]);
+
+    harness.press(&[KeyCode::Char('w'), KeyCode::Char('j')]);
+    let mut screen = harness.screen();
+    // the store path depends on the temporary folder
+    assert!(screen[1].ends_with(".todos"));
+    screen[1].clear();
+    assert_eq!(
+        screen,
+        [
+            "                       TODO - general",
+            "",
+            "                            List",
+            ">   ☐ Create a task",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "                        Description",
+            " ☐ TODO: Press 'a' to start writing a task",
+            " #1, created less than a minute ago",
+            "",
+            "                          History",
+            " >   ✓ Write tests ⏱ 0:00:00",
+            "",
+            "     a add, e edit, → status, d delete, u undo, / search",
+            " t topics, q quit, w switch list, A add subtask, Space fold",
+        ]
+    );
+    // the selected row of the focused list is highlighted
+    let buffer = harness.terminal.backend().buffer();
+    assert!(buffer[(6, 14)].modifier.contains(Modifier::BOLD));
+    assert_eq!(buffer[(6, 14)].fg, theme().completed_fg);
+
+    // delete it from the history, once confirmed
+    harness.press(&[KeyCode::Char('d')]);
+    let screen = harness.screen();
+    assert!(screen
+        .iter()
+        .any(|row| row.contains("Delete 'Write tests'?")));
+    harness.press(&[KeyCode::Char('n')]);
+    assert_eq!(harness.history("todo"), ["Write tests"]);
+    harness.press(&[KeyCode::Char('d'), KeyCode::Char('y')]);
+    assert!(harness.history("todo").is_empty());
+}
+
+#[test]
+fn test_ids_of_deleted_tasks_are_not_given_again() {
+    let mut harness = Harness::new("ids");
+    harness.press(&[KeyCode::Char('a')]);
+    harness.type_text("first");
+    harness.press(&[KeyCode::Enter]);
+    assert_eq!(harness.todo("id"), [1, 2]);
+
+    harness.press(&[KeyCode::Char('j'), KeyCode::Char('j')]);
+    harness.press(&[KeyCode::Char('d'), KeyCode::Char('y')]);
+    harness.press(&[KeyCode::Char('a')]);
+    harness.type_text("second");
+    harness.press(&[KeyCode::Enter]);
+    assert_eq!(harness.todo("id"), [1, 3]);
+
+    // nor once undone, or after reopening the topic
+    harness.press(&[KeyCode::Char('u')]);
+    harness.app = App::new(harness.dir.store(), utils::DEFAULT_TOPIC);
+    harness.press(&[KeyCode::Char('a')]);
+    harness.type_text("third");
+    harness.press(&[KeyCode::Enter]);
+    assert_eq!(harness.todo("id"), [1, 4]);
+    let last_id = harness.dir.store().root().join("general.last-id");
+    assert_eq!(fs::read_to_string(last_id).unwrap(), "4\n");
+}
+
+#[test]
+fn test_unreadable_topic_is_reported_and_kept() {
+    let mut harness = Harness::new("unreadable");
+    let file = harness.dir.store().root().join("broken.json");
+    fs::write(&file, "not json").unwrap();
+    harness.app = App::new(harness.dir.store(), "broken");
+    let screen = harness.screen().join("\n");
+    assert!(screen.contains("Could not load topic"));
+
+    // edits are not written over the file
+    harness.press(&[KeyCode::Enter, KeyCode::Char('a')]);
+    harness.type_text("new");
+    harness.press(&[KeyCode::Enter]);
+    assert!(harness.app.save_error.is_some());
+    assert_eq!(fs::read_to_string(&file).unwrap(), "not json");
+
+    // nor lost by switching to another topic
+    harness.press(&[KeyCode::Enter, KeyCode::Char('t'), KeyCode::Char('n')]);
+    harness.type_text("other");
+    harness.press(&[KeyCode::Enter]);
+    assert_eq!(harness.app.topic, "broken");
+    assert!(harness
+        .app
+        .todo_list
+        .items
+        .iter()
+        .any(|item| item.todo == "new"));
+    let screen = harness.screen().join("\n");
+    assert!(screen.contains("Not switching topics."));
+}
+
+#[test]
+fn test_restore_from_archive() {
+    let mut harness = Harness::new("restore");
+    let store = harness.dir.store();
+    let mut archived = TodoItem::new(Status::Completed, "Old task", "");
+    archived.id = 7;
+    store
+        .save_list(
+            &[archived],
+            &utils::archive_file(utils::DEFAULT_TOPIC, "2025-01"),
+        )
+        .unwrap();
+
+    harness.press(&[KeyCode::Char('Z')]);
+    let screen = harness.screen();
+    assert!(screen
+        .iter()
+        .any(|row| row.contains("> 2025-01 ✓ Old task")));
+    // the footer lists the keys of the archive only
+    assert!(screen[HEIGHT as usize - 2].contains("r restore, Esc lists"));
+
+    harness.press(&[KeyCode::Char('r')]);
+    assert_eq!(harness.todo("todo"), ["Create a task", "Old task"]);
+    assert_eq!(harness.todo("status"), ["TODO", "TODO"]);
+    assert!(store.list_archives(utils::DEFAULT_TOPIC).is_empty());
+}
+
+#[test]
+fn test_failed_restore_stays_archived() {
+    let mut harness = Harness::new("restore-failed");
+    let store = harness.dir.store();
+    let archived = TodoItem::new(Status::Completed, "Old task", "");
+    store
+        .save_list(&[archived], &utils::archive_file("broken", "2025-01"))
+        .unwrap();
+    fs::write(harness.dir.store().root().join("broken.json"), "not json").unwrap();
+    harness.app = App::new(store, "broken");
+
+    // the list cannot be saved, so the task is left in the archive only
+    harness.press(&[KeyCode::Enter, KeyCode::Char('Z'), KeyCode::Char('r')]);
+    assert!(harness.app.save_error.is_some());
+    assert!(harness.app.todo_list.items.is_empty());
+    let view = harness.app.archive.as_ref().unwrap();
+    assert_eq!(view.list.items.len(), 1);
+    let store = harness.dir.store();
+    assert_eq!(store.list_archives("broken"), ["2025-01"]);
+}
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+use crate::base::{Priority, Status, TodoItem};
+use crate::tree::{self, ItemPath};
+
+const DATE_FORMAT: &str = "%Y%m%d";
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+/// Lines longer than this many bytes are folded, as required by RFC 5545.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Write the items as an iCalendar file of `VTODO` components.
+///
+/// Subtasks are written as their own component, linked to their parent with `RELATED-TO`.
+pub fn export(items: &[TodoItem]) -> String {
+    let mut out = String::new();
+    write_line(&mut out, "BEGIN:VCALENDAR");
+    write_line(&mut out, "VERSION:2.0");
+    write_line(&mut out, "PRODID:-//todo//todo list//EN");
+    write_items(&mut out, items, None);
+    write_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn write_items(out: &mut String, items: &[TodoItem], parent: Option<&str>) {
+    for item in items {
+        let uid = uid(item);
+        write_line(out, "BEGIN:VTODO");
+        write_line(out, &format!("UID:{uid}"));
+        write_line(out, &format!("DTSTAMP:{}", format_time(item.updated_at)));
+        write_line(out, &format!("CREATED:{}", format_time(item.created_at)));
+        write_line(
+            out,
+            &format!("LAST-MODIFIED:{}", format_time(item.updated_at)),
+        );
+        write_line(out, &format!("SUMMARY:{}", escape(&item.todo)));
+        if !item.info.is_empty() {
+            write_line(out, &format!("DESCRIPTION:{}", escape(&item.info)));
+        }
+        let status = match item.status {
+            Status::Todo => "NEEDS-ACTION",
+            Status::InProgress => "IN-PROCESS",
+            Status::Completed => "COMPLETED",
+        };
+        write_line(out, &format!("STATUS:{status}"));
+        if let Some(completed_at) = item.completed_at {
+            write_line(out, &format!("COMPLETED:{}", format_time(completed_at)));
+        }
+        if let Some(due) = item.due {
+            write_line(out, &format!("DUE;VALUE=DATE:{}", due.format(DATE_FORMAT)));
+        }
+        if let Some(priority) = item.priority {
+            let priority = match priority {
+                Priority::P0 => 1,
+                Priority::P1 => 3,
+                Priority::P2 => 5,
+                Priority::P3 => 7,
+            };
+            write_line(out, &format!("PRIORITY:{priority}"));
+        }
+        if !item.tags.is_empty() {
+            let tags: Vec<String> = item.tags.iter().map(|tag| escape(tag)).collect();
+            write_line(out, &format!("CATEGORIES:{}", tags.join(",")));
+        }
+        if let Some(parent) = parent {
+            write_line(out, &format!("RELATED-TO:{parent}"));
+        }
+        write_line(out, "END:VTODO");
+        write_items(out, &item.children, Some(&uid));
+    }
+}
+
+fn uid(item: &TodoItem) -> String {
+    format!("{}-{}@todo", item.created_at.timestamp(), item.id)
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format(DATE_TIME_FORMAT).to_string()
+}
+
+/// Write a content line, folded into lines of at most 75 bytes.
+fn write_line(out: &mut String, line: &str) {
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            out.push_str("\r\n ");
+            // the leading space counts in the length of the continuation line
+            length = 1;
+        }
+        out.push(c);
+        length += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Split a comma separated value, ignoring escaped commas.
+fn split_list(value: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            ',' if !escaped => parts.push(String::new()),
+            _ => {
+                escaped = c == '\\' && !escaped;
+                if let Some(part) = parts.last_mut() {
+                    part.push(c);
+                }
+            }
+        }
+    }
+    parts
+        .iter()
+        .map(|part| unescape(part).trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+/// Read the `VTODO` components of an iCalendar file, ignoring the other components.
+///
+/// Components related to another one with `RELATED-TO` become its subtasks.
+pub fn import(content: &str) -> Vec<TodoItem> {
+    // unfold the continuation lines first
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut items: Vec<TodoItem> = Vec::new();
+    let mut paths: HashMap<String, ItemPath> = HashMap::new();
+    let mut current: Option<(TodoItem, Option<String>, Option<String>)> = None;
+    // components nested in the current task, such as its alarms, have properties of their own
+    let mut depth = 0;
+    for line in &lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // parameters such as `;VALUE=DATE` are not needed to read the values
+        let name = name.split(';').next().unwrap_or_default().to_uppercase();
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VTODO") => {
+                current = Some((TodoItem::new(Status::Todo, "", ""), None, None));
+            }
+            ("BEGIN", Some(_)) => depth += 1,
+            ("END", Some(_)) if depth > 0 => depth -= 1,
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VTODO") => {
+                let Some((mut item, uid, parent)) = current.take() else {
+                    continue;
+                };
+                if item.todo.is_empty() {
+                    item.todo = "Untitled".to_string();
+                }
+                let parent_path = parent.and_then(|parent| paths.get(&parent).cloned());
+                let path = match parent_path
+                    .as_ref()
+                    .and_then(|path| Some((path, tree::get_mut(&mut items, path)?)))
+                {
+                    Some((parent_path, parent)) => {
+                        parent.children.push(item);
+                        let mut path = parent_path.clone();
+                        path.push(parent.children.len() - 1);
+                        path
+                    }
+                    None => {
+                        items.push(item);
+                        vec![items.len() - 1]
+                    }
+                };
+                if let Some(uid) = uid {
+                    paths.insert(uid, path);
+                }
+            }
+            (_, None) => {}
+            (_, Some(_)) if depth > 0 => {}
+            (_, Some((item, uid, parent))) => match name.as_str() {
+                "UID" => *uid = Some(value.to_string()),
+                "RELATED-TO" => *parent = Some(value.to_string()),
+                "SUMMARY" => item.todo = unescape(value),
+                "DESCRIPTION" => item.info = unescape(value),
+                "STATUS" => {
+                    let status = match value.to_uppercase().as_str() {
+                        "IN-PROCESS" => Status::InProgress,
+                        "COMPLETED" | "CANCELLED" => Status::Completed,
+                        _ => Status::Todo,
+                    };
+                    item.status = status;
+                }
+                "DUE" => {
+                    item.due = value
+                        .get(..8)
+                        .and_then(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok());
+                }
+                "PRIORITY" => {
+                    item.priority = match value.trim().parse::<u8>() {
+                        Ok(1..=2) => Some(Priority::P0),
+                        Ok(3..=4) => Some(Priority::P1),
+                        Ok(5..=6) => Some(Priority::P2),
+                        Ok(7..=9) => Some(Priority::P3),
+                        _ => None,
+                    };
+                }
+                "CATEGORIES" => item.tags.extend(split_list(value)),
+                "CREATED" => {
+                    if let Some(time) = parse_time(value) {
+                        item.created_at = time;
+                    }
+                }
+                "LAST-MODIFIED" => {
+                    if let Some(time) = parse_time(value) {
+                        item.updated_at = time;
+                    }
+                }
+                "COMPLETED" => item.completed_at = parse_time(value),
+                _ => {}
+            },
+        }
+    }
+    // the status and the completion time may come in any order
+    tree::for_each_mut(&mut items, &mut |item| {
+        if item.status != Status::Completed {
+            item.completed_at = None;
+        } else if item.completed_at.is_none() {
+            item.completed_at = Some(item.updated_at);
+        }
+    });
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut parent = TodoItem::new(
+            Status::InProgress,
+            "Release, then rest",
+            "first line\nsecond; line",
+        );
+        parent.id = 1;
+        parent.due = NaiveDate::from_ymd_opt(2026, 3, 1);
+        parent.priority = Some(Priority::P1);
+        parent.tags = vec!["work".to_string(), "a,b".to_string()];
+        let mut child = TodoItem::new(Status::Completed, &"long ".repeat(30), "");
+        child.id = 2;
+        parent.children.push(child);
+
+        let ics = export(&[parent.clone(), TodoItem::new(Status::Todo, "other", "")]);
+        assert!(ics.lines().all(|line| line.len() <= MAX_LINE_LENGTH + 1));
+        assert!(ics.contains("DUE;VALUE=DATE:20260301\r\n"));
+        assert!(ics.contains("STATUS:IN-PROCESS\r\n"));
+
+        let items = import(&ics);
+        assert_eq!(items.len(), 2);
+        let imported = &items[0];
+        assert_eq!(imported.todo, parent.todo);
+        assert_eq!(imported.info, parent.info);
+        assert_eq!(imported.status, Status::InProgress);
+        assert_eq!(imported.due, parent.due);
+        assert_eq!(imported.priority, parent.priority);
+        assert_eq!(imported.tags, parent.tags);
+        assert_eq!(
+            imported.created_at.timestamp(),
+            parent.created_at.timestamp()
+        );
+        assert_eq!(imported.children.len(), 1);
+        assert_eq!(imported.children[0].todo, parent.children[0].todo);
+        assert_eq!(imported.children[0].status, Status::Completed);
+        assert!(imported.children[0].completed_at.is_some());
+    }
+
+    #[test]
+    fn test_import_ignores_events() {
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:meeting\nEND:VEVENT\nBEGIN:VTODO\nSUMMARY:call\n back\nSTATUS:NEEDS-ACTION\nDUE:20260102T090000Z\nEND:VTODO\nEND:VCALENDAR\n";
+        let items = import(ics);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].todo, "callback");
+        assert_eq!(items[0].due, NaiveDate::from_ymd_opt(2026, 1, 2));
+    }
+
+    #[test]
+    fn test_import_skips_nested_alarms() {
+        let ics = "BEGIN:VCALENDAR\nBEGIN:VTODO\nSUMMARY:Pay rent\nDESCRIPTION:bank transfer\nBEGIN:VALARM\nACTION:DISPLAY\nDESCRIPTION:Reminder\nSUMMARY:Alarm\nEND:VALARM\nEND:VTODO\nBEGIN:VTODO\nSUMMARY:next\nEND:VTODO\nEND:VCALENDAR\n";
+        let items = import(ics);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].todo, "Pay rent");
+        assert_eq!(items[0].info, "bank transfer");
+        assert_eq!(items[1].todo, "next");
+    }
+}
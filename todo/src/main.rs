@@ -5,51 +5,134 @@
 /// release.
 ///
 /// [`latest`]: https://github.com/ratatui/ratatui/tree/latest
+use chrono::Utc;
+use clap::Parser;
 use color_eyre::Result;
 use ratatui::{
     buffer::Buffer,
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-    layout::{Constraint, Layout, Rect},
-    style::{
-        palette::tailwind::{BLUE, GREEN, ORANGE, SLATE},
-        Color, Modifier, Style, Stylize,
+    crossterm::{
+        event::{
+            self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste,
+            EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, KeyboardEnhancementFlags,
+            MouseButton, MouseEvent, MouseEventKind, PopKeyboardEnhancementFlags,
+            PushKeyboardEnhancementFlags,
+        },
+        execute,
+        terminal::supports_keyboard_enhancement,
     },
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Color, Modifier, Style, Stylize},
     symbols,
-    text::Line,
+    text::{Line, Span},
     widgets::{
         Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Padding, Paragraph,
         StatefulWidget, Widget, Wrap,
     },
     DefaultTerminal,
 };
+use std::io::stdout;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
+mod archive;
 mod base;
+mod board;
+mod cli;
+mod config;
+mod filter;
 mod form;
+#[cfg(test)]
+mod headless;
+mod ical;
+mod markdown;
+mod merge;
+mod modal;
 mod popup;
+mod recurrence;
+mod scan;
+mod sort;
+mod stats;
+mod topics;
+mod tree;
+mod undo;
 mod utils;
+mod watch;
 use base::{Status, TodoItem};
+use board::Board;
+use config::{theme, Action, Keymap, View};
+use filter::Filter;
 use form::TodoForm;
+use modal::{Modal, Outcome};
+use sort::SortMode;
+use stats::Stats;
+use topics::TopicPanel;
+use tree::ItemPath;
+use undo::{Snapshot, UndoStack};
+use utils::{Store, DEFAULT_TOPIC};
+use watch::StoreWatcher;
 
-const TODO_HEADER_STYLE: Style = Style::new().fg(SLATE.c100).bg(BLUE.c800);
-const NORMAL_ROW_BG: Color = SLATE.c950;
-const ALT_ROW_BG_COLOR: Color = SLATE.c900;
-const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
-const TEXT_FG_COLOR: Color = SLATE.c200;
-const COMPLETED_TEXT_FG_COLOR: Color = GREEN.c500;
-const IN_PROGRESS_TEXT_FG_COLOR: Color = ORANGE.c300;
+/// How often the store is checked for changes made by other processes, while waiting for events.
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
 
-const DEFAULT_TOPIC: &str = "general";
-const DEFAULT_HISTORY: &str = "history";
+/// Longest delay between the two clicks of a double click.
+const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(400);
 
 pub struct TodoList {
     items: Vec<TodoItem>,
     state: ListState,
+    /// Order the items were last sorted in, `None` once reordered by hand.
+    sort_mode: Option<SortMode>,
+    /// Where the rows were last rendered, to find the item under the mouse.
+    area: Rect,
+}
+
+/// Project-specific todo list, opening the TUI when no command is given
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Topic to open, stored as `.todos/<topic>.json`
+    #[arg(short, long, default_value = DEFAULT_TOPIC, global = true)]
+    topic: String,
+    /// Store folder to use instead of the nearest `.todos` or the global one
+    #[arg(short = 'D', long, global = true)]
+    dir: Option<PathBuf>,
+    /// Run a command instead of opening the TUI
+    #[command(subcommand)]
+    command: Option<cli::Command>,
 }
 
 fn main() -> Result<()> {
     color_eyre::install()?;
+    let args = Args::parse();
+    if !utils::is_valid_topic(&args.topic) {
+        return Err(color_eyre::eyre::eyre!(
+            "invalid topic name '{}', use letters, digits, '-' or '_'",
+            args.topic
+        ));
+    }
+    let store = Store::discover(args.dir);
+    if let Some(command) = args.command {
+        return cli::run(command, &store, &args.topic);
+    }
+    let (keymap, theme) = config::load().map_err(|err| color_eyre::eyre::eyre!(err))?;
+    config::set_theme(theme);
     let terminal = ratatui::init();
-    let app_result = App::default().run(terminal);
+    execute!(stdout(), EnableBracketedPaste, EnableMouseCapture)?;
+    // lets the terminal report Shift-Enter, used for newlines in the description
+    let enhanced_keyboard = supports_keyboard_enhancement().unwrap_or(false);
+    if enhanced_keyboard {
+        execute!(
+            stdout(),
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )?;
+    }
+    let mut app = App::new(store, &args.topic);
+    app.keymap = keymap;
+    let app_result = app.run(terminal);
+    if enhanced_keyboard {
+        execute!(stdout(), PopKeyboardEnhancementFlags)?;
+    }
+    execute!(stdout(), DisableMouseCapture, DisableBracketedPaste)?;
     ratatui::restore();
     app_result
 }
@@ -63,48 +146,330 @@ fn main() -> Result<()> {
 struct App {
     should_exit: bool,
     focus_history: bool,
+    store: Store,
+    topic: String,
     todo_list: TodoList,
     history_list: TodoList,
     todo_form: TodoForm,
     popup_mode: bool,
+    form_target: FormTarget,
+    topic_panel: Option<TopicPanel>,
+    /// Dialog taking the key presses until it is closed.
+    modal: Option<Modal<ModalAction>>,
+    /// Statistics shown instead of the lists.
+    stats: Option<Stats>,
+    /// Board shown instead of the lists.
+    board: Option<Board>,
+    /// Archived tasks shown instead of the lists.
+    archive: Option<ArchiveView>,
+    filter: Filter,
+    undo: UndoStack,
+    keymap: Keymap,
+    /// Whether the lists changed since they were last saved.
+    dirty: bool,
+    /// Content of the topic files as last read or written, to merge the changes made by others.
+    disk: Snapshot,
+    /// Highest id ever given in the topic, as ids are never given again.
+    last_id: u64,
+    save_error: Option<String>,
+    /// Message shown in the footer until the next key press.
+    warning: Option<String>,
+    /// Terminal cursor shown while typing in a text field.
+    cursor: Option<Position>,
+    /// Where the form fields were last rendered.
+    form_area: Rect,
+    /// Time and position of the last click, to detect double clicks.
+    last_click: Option<(Instant, Position)>,
 }
 
-impl Default for App {
-    fn default() -> Self {
-        let todos: Vec<TodoItem> =
-            utils::load_todo_items((DEFAULT_TOPIC.to_string() + ".json").as_str()).unwrap();
-        let history: Vec<TodoItem> =
-            utils::load_todo_items((DEFAULT_HISTORY.to_string() + ".json").as_str()).unwrap();
-        if todos.len() > 0 {
-            Self {
-                should_exit: false,
-                focus_history: false,
-                todo_list: TodoList {
-                    items: todos,
-                    state: ListState::default(),
-                },
-                history_list: TodoList {
-                    items: history,
-                    state: ListState::default(),
-                },
-                todo_form: TodoForm::default(),
-                popup_mode: false,
+impl App {
+    /// Open a topic of the store, reporting why when it cannot be read.
+    fn new(store: Store, topic: &str) -> Self {
+        match App::load_topic(&store, topic) {
+            Ok((todo_list, history_list, disk, last_id)) => {
+                let mut app = App::with_lists(store, topic, todo_list, history_list, last_id);
+                app.disk = disk;
+                app
+            }
+            Err(err) => {
+                let empty = || TodoList::new(Vec::new());
+                let mut app = App::with_lists(store, topic, empty(), empty(), 0);
+                app.modal = Some(Modal::error(format!(
+                    "Could not load topic '{topic}', it will not be saved: {err}"
+                )));
+                app
             }
+        }
+    }
+
+    fn with_lists(
+        store: Store,
+        topic: &str,
+        mut todo_list: TodoList,
+        mut history_list: TodoList,
+        mut last_id: u64,
+    ) -> Self {
+        // items saved before ids were introduced get one here
+        base::assign_ids(&mut todo_list.items, &mut history_list.items, &mut last_id);
+        let mut app = Self {
+            should_exit: false,
+            focus_history: false,
+            store,
+            topic: topic.to_string(),
+            todo_list,
+            history_list,
+            todo_form: TodoForm::default(),
+            popup_mode: false,
+            form_target: FormTarget::New,
+            topic_panel: None,
+            modal: None,
+            stats: None,
+            board: None,
+            archive: None,
+            filter: Filter::default(),
+            undo: UndoStack::default(),
+            keymap: Keymap::default(),
+            dirty: false,
+            disk: Snapshot {
+                todo: Vec::new(),
+                history: Vec::new(),
+            },
+            last_id,
+            save_error: None,
+            warning: None,
+            cursor: None,
+            form_area: Rect::default(),
+            last_click: None,
+        };
+        app.disk = app.snapshot();
+        app
+    }
+
+    /// Read the todo and history lists of a topic from the store, along with the content of the
+    /// files and the highest id given in the topic.
+    fn load_topic(
+        store: &Store,
+        topic: &str,
+    ) -> Result<(TodoList, TodoList, Snapshot, u64), Box<dyn std::error::Error>> {
+        let mut disk = App::read_topic(store, topic)?;
+        let mut last_id = store.load_last_id(topic)?;
+        base::assign_ids(&mut disk.todo, &mut disk.history, &mut last_id);
+        let mut todos = disk.todo.clone();
+        // the code topic is kept in sync with the comments each time it is opened
+        if topic == scan::CODE_TOPIC {
+            scan::sync(&mut todos, &scan::scan_tree(&store.project_root()));
+        }
+        let todo_list = if !todos.is_empty() {
+            TodoList::new(todos)
         } else {
-            Self {
-                should_exit: false,
-                focus_history: false,
-                todo_list: TodoList::from_iter([(
-                    Status::Todo,
-                    "Create a task",
-                    "Press 'a' to start writing a task",
-                )]),
-                history_list: TodoList::from_iter([]),
-                todo_form: TodoForm::default(),
-                popup_mode: false,
+            TodoList::from_iter([(
+                Status::Todo,
+                "Create a task",
+                "Press 'a' to start writing a task",
+            )])
+        };
+        let history_list = TodoList::new(disk.history.clone());
+        Ok((todo_list, history_list, disk, last_id))
+    }
+
+    fn read_topic(store: &Store, topic: &str) -> Result<Snapshot, Box<dyn std::error::Error>> {
+        Ok(Snapshot {
+            todo: store.load_todo_items(&utils::topic_file(topic))?,
+            history: store.load_todo_items(&utils::history_file(topic))?,
+        })
+    }
+
+    /// Merge the changes made to the topic files by other processes, keeping the selection.
+    fn reload(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let remote = App::read_topic(&self.store, &self.topic)?;
+        // other processes may have given ids to tasks deleted since
+        let last_id = self.store.load_last_id(&self.topic)?;
+        self.last_id = self.last_id.max(last_id);
+        if remote == self.disk {
+            return Ok(());
+        }
+        let local = self.snapshot();
+        let (merged, conflicts) = merge::merge(&self.disk, &local, &remote, &mut self.last_id);
+        let todo_selected = self.todo_list.selected_id(&self.filter);
+        let history_selected = self.history_list.selected_id(&self.filter);
+        self.dirty = merged != remote;
+        // the snapshots lack the external changes, restoring one would overwrite them
+        if merged != self.snapshot() {
+            self.undo.clear();
+        }
+        self.restore(merged);
+        self.todo_list.select_id(todo_selected, &self.filter);
+        self.history_list.select_id(history_selected, &self.filter);
+        self.disk = remote;
+        if conflicts > 0 {
+            self.warning = Some(format!(
+                "{conflicts} tasks were changed both here and on disk, kept the version on disk"
+            ));
+        }
+        Ok(())
+    }
+
+    fn save(&mut self) {
+        // never overwrite changes that were not merged yet, nor files that could not be read
+        // the history goes first, so that a crash in between a flush duplicates the moved
+        // tasks instead of losing them, and the last id before both, so that it is never behind
+        let result = self.reload().and_then(|()| {
+            self.store.save_last_id(&self.topic, self.last_id)?;
+            self.store
+                .save_list(&self.history_list.items, &utils::history_file(&self.topic))?;
+            self.store
+                .save_list(&self.todo_list.items, &utils::topic_file(&self.topic))?;
+            Ok(())
+        });
+        // keep the lists dirty on failure so that the next event retries
+        self.dirty = result.is_err();
+        match result {
+            Ok(()) => {
+                self.disk = self.snapshot();
+                self.save_error = None;
+            }
+            Err(err) => {
+                let message = format!("Could not save topic '{}': {err}", self.topic);
+                // retries keep failing silently, the error stays in the footer
+                if self.save_error.is_none() {
+                    self.modal = Some(Modal::error(message.clone()));
+                }
+                self.save_error = Some(message);
             }
         }
     }
+
+    /// Save the current topic then open another one, staying on the current one when its lists
+    /// could not be saved.
+    fn switch_topic(&mut self, topic: &str) {
+        self.save();
+        if let Some(err) = &self.save_error {
+            // opening the other topic would lose the unsaved lists
+            self.modal = Some(Modal::error(format!("Not switching topics. {err}")));
+            return;
+        }
+        self.open_topic(topic);
+    }
+
+    /// Replace the lists with the ones of another topic, without saving the current ones.
+    ///
+    /// The current topic stays open when the other one cannot be read.
+    fn open_topic(&mut self, topic: &str) {
+        let (mut todo_list, mut history_list, disk, mut last_id) =
+            match App::load_topic(&self.store, topic) {
+                Ok(lists) => lists,
+                Err(err) => {
+                    self.modal = Some(Modal::error(format!(
+                        "Could not load topic '{topic}': {err}"
+                    )));
+                    return;
+                }
+            };
+        base::assign_ids(&mut todo_list.items, &mut history_list.items, &mut last_id);
+        self.disk = disk;
+        self.last_id = last_id;
+        self.topic = topic.to_string();
+        self.todo_list = todo_list;
+        self.history_list = history_list;
+        self.focus_history = false;
+        self.undo.clear();
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            todo: self.todo_list.items.clone(),
+            history: self.history_list.items.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.todo_list.items = snapshot.todo;
+        self.history_list.items = snapshot.history;
+        self.dirty = true;
+    }
+
+    /// Record the lists before a mutation so that it can be undone, and flag them for autosave.
+    fn checkpoint(&mut self) {
+        let snapshot = self.snapshot();
+        self.undo.record(snapshot);
+        self.dirty = true;
+    }
+
+    fn undo(&mut self) {
+        let current = self.snapshot();
+        if let Some(previous) = self.undo.undo(current) {
+            self.restore(previous);
+        }
+    }
+
+    fn redo(&mut self) {
+        let current = self.snapshot();
+        if let Some(next) = self.undo.redo(current) {
+            self.restore(next);
+        }
+    }
+}
+
+/// Archived tasks browsed instead of the lists, along with the month of their archive.
+struct ArchiveView {
+    months: Vec<String>,
+    list: TodoList,
+}
+
+/// What to do once a dialog is accepted.
+enum ModalAction {
+    Delete,
+    Flush,
+    NewTopic,
+    RenameTopic,
+    DeleteTopic,
+}
+
+/// What the form popup writes to when submitted.
+///
+/// Items are kept by id, as the merge of external changes can move them while the form is open.
+enum FormTarget {
+    New,
+    Child(u64),
+    Edit(u64),
+}
+
+impl TodoList {
+    fn new(items: Vec<TodoItem>) -> Self {
+        Self {
+            items,
+            state: ListState::default(),
+            sort_mode: None,
+            area: Rect::default(),
+        }
+    }
+
+    /// Paths in `items` of the rows shown once the filter is applied.
+    fn visible(&self, filter: &Filter) -> Vec<ItemPath> {
+        tree::flatten(&self.items, &|item| filter.matches(item))
+    }
+
+    /// Path in `items` of the selected row.
+    fn selected_path(&self, filter: &Filter) -> Option<ItemPath> {
+        let selected = self.state.selected()?;
+        self.visible(filter).into_iter().nth(selected)
+    }
+
+    fn selected_id(&self, filter: &Filter) -> Option<u64> {
+        let path = self.selected_path(filter)?;
+        tree::get(&self.items, &path).map(|item| item.id)
+    }
+
+    fn select_id(&mut self, id: Option<u64>, filter: &Filter) {
+        let path = id.and_then(|id| tree::find(&self.items, &|item| item.id == id));
+        self.select_path(path, filter);
+    }
+
+    /// Select the row showing the item at `path`, if it is still visible.
+    fn select_path(&mut self, path: Option<ItemPath>, filter: &Filter) {
+        let row = path.and_then(|path| self.visible(filter).iter().position(|p| *p == path));
+        self.state.select(row);
+    }
 }
 
 impl FromIterator<(Status, &'static str, &'static str)> for TodoList {
@@ -113,67 +478,512 @@ impl FromIterator<(Status, &'static str, &'static str)> for TodoList {
             .into_iter()
             .map(|(status, todo, info)| TodoItem::new(status, todo, info))
             .collect();
-        let state = ListState::default();
-        Self { items, state }
+        Self::new(items)
     }
 }
 
 impl App {
     fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
+        // without a watcher, external changes are still merged before each save
+        let watcher = StoreWatcher::new(self.store.root()).ok();
         while !self.should_exit {
-            terminal.draw(|frame| frame.render_widget(&mut self, frame.area()))?;
-            if let Event::Key(key) = event::read()? {
-                if self.popup_mode {
-                    if (key.code == KeyCode::Esc) | (key.code == KeyCode::Enter) {
-                        self.popup_mode = !self.popup_mode;
-                        // get the form values and add it to the todo list
-                        let todo = self.todo_form.extract();
-                        if key.code == KeyCode::Enter {
-                            self.todo_list.items.push(todo);
-                        }
-                    } else {
-                        self.todo_form.on_key_press(key);
-                    }
+            terminal.draw(|frame| {
+                frame.render_widget(&mut self, frame.area());
+                if let Some(position) = self.cursor {
+                    frame.set_cursor_position(position);
+                }
+            })?;
+            if event::poll(WATCH_INTERVAL)? {
+                self.handle_event(event::read()?);
+            }
+            let files = [
+                utils::topic_file(&self.topic),
+                utils::history_file(&self.topic),
+            ];
+            if watcher
+                .as_ref()
+                .is_some_and(|watcher| watcher.changed(&files))
+            {
+                // a file that cannot be read is reported by the next save
+                _ = self.reload();
+            }
+            // autosave after every mutation
+            if self.dirty {
+                self.save();
+            }
+        }
+        // retry a failed save before giving up
+        if self.dirty {
+            self.save();
+        }
+        match self.save_error {
+            Some(err) => Err(color_eyre::eyre::eyre!(err)),
+            None => Ok(()),
+        }
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Paste(text) => self.handle_paste(&text),
+            Event::Mouse(mouse) => self.handle_mouse(mouse),
+            Event::Key(key) => {
+                self.warning = None;
+                self.handle_key_event(key);
+            }
+            _ => {}
+        }
+    }
+
+    /// Send a key to the dialog, panel, form or search field being edited, else to the list.
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        if self.modal.is_some() {
+            self.handle_modal_key(key);
+        } else if self.topic_panel.is_some() {
+            self.handle_topic_key(key);
+        } else if self.popup_mode {
+            if key.code == KeyCode::Esc {
+                self.popup_mode = false;
+                self.form_target = FormTarget::New;
+                self.todo_form.clear();
+            } else if key.code == KeyCode::Enter
+                && key.modifiers.is_empty()
+                && self.todo_form.is_valid()
+            {
+                self.submit_form();
+            } else {
+                self.todo_form.on_key_press(key);
+            }
+        } else if self.filter.typing {
+            self.handle_search_key(key);
+        } else {
+            self.handle_key(key);
+        }
+    }
+
+    fn handle_paste(&mut self, text: &str) {
+        if let Some(modal) = self.modal.as_mut() {
+            modal.on_paste(text);
+        } else if self.popup_mode {
+            self.todo_form.on_paste(text);
+        } else if self.filter.typing {
+            self.update_filter(|filter| filter.query.on_paste(text));
+        }
+    }
+
+    /// Select with a click, toggle with a double click, scroll with the wheel, and focus the
+    /// clicked field of the form.
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if self.modal.is_some()
+            || self.topic_panel.is_some()
+            || self.stats.is_some()
+            || self.board.is_some()
+            || self.archive.is_some()
+        {
+            return;
+        }
+        let position = Position::new(mouse.column, mouse.row);
+        if self.popup_mode {
+            if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+                self.todo_form.focus_at(self.form_area, position);
+            }
+            return;
+        }
+        let in_history = if self.history_list.area.contains(position) {
+            true
+        } else if self.todo_list.area.contains(position) {
+            false
+        } else {
+            return;
+        };
+        match mouse.kind {
+            MouseEventKind::ScrollDown => {
+                self.focus_history = in_history;
+                self.select_next();
+            }
+            MouseEventKind::ScrollUp => {
+                self.focus_history = in_history;
+                self.select_previous();
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.focus_history = in_history;
+                let list = self.focused_list();
+                let row = list.state.offset() + usize::from(position.y - list.area.y);
+                if row >= list.visible(&self.filter).len() {
+                    return;
+                }
+                self.focused_list_mut().state.select(Some(row));
+                let now = Instant::now();
+                let double_click = self.last_click.is_some_and(|(time, last)| {
+                    now - time < DOUBLE_CLICK_DELAY && last.y == position.y
+                });
+                if double_click {
+                    self.last_click = None;
+                    self.toggle_status();
                 } else {
-                    self.handle_key(key);
+                    self.last_click = Some((now, position));
                 }
-            };
+            }
+            _ => {}
         }
-        // save the json with updated status
-        utils::save_list(
-            self.todo_list.items,
-            (DEFAULT_TOPIC.to_string() + ".json").as_str(),
-        );
-        utils::save_list(
-            self.history_list.items,
-            (DEFAULT_HISTORY.to_string() + ".json").as_str(),
-        );
-        Ok(())
     }
 
-    fn handle_key(&mut self, key: KeyEvent) {
+    fn handle_search_key(&mut self, key: KeyEvent) {
         if key.kind != KeyEventKind::Press {
             return;
         }
         match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => self.should_exit = true,
-            KeyCode::Char('h') | KeyCode::Left => self.select_none(),
-            KeyCode::Char('j') | KeyCode::Down => self.select_next(),
-            KeyCode::Char('k') | KeyCode::Up => self.select_previous(),
-            KeyCode::Char('g') | KeyCode::Home => self.select_first(),
-            KeyCode::Char('G') | KeyCode::End => self.select_last(),
-            KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
-                self.toggle_status();
-            }
-            KeyCode::Char('w') => self.switch_todo_history(),
-            KeyCode::Char('f') => self.flush_items(),
-            KeyCode::Char('a') => self.add_todo(),
-            KeyCode::Char('d') => self.delete_task(),
-            KeyCode::Char('s') => self.sort_list(),
+            KeyCode::Esc => self.update_filter(|filter| filter.query.set_value("")),
+            KeyCode::Enter => {}
+            _ => self.update_filter(|filter| filter.query.on_key_press(key)),
+        }
+        if matches!(key.code, KeyCode::Esc | KeyCode::Enter) {
+            self.filter.typing = false;
+        }
+    }
+
+    /// Change the filter while keeping the selection on the same underlying items.
+    fn update_filter(&mut self, change: impl FnOnce(&mut Filter)) {
+        let todo_path = self.todo_list.selected_path(&self.filter);
+        let history_path = self.history_list.selected_path(&self.filter);
+        change(&mut self.filter);
+        self.todo_list.select_path(todo_path, &self.filter);
+        self.history_list.select_path(history_path, &self.filter);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        let Some(action) = self.keymap.action(&key) else {
+            return;
+        };
+        if self.stats.is_some() {
+            match action {
+                Action::Quit => self.should_exit = true,
+                Action::Back | Action::Stats => self.stats = None,
+                _ => {}
+            }
+            return;
+        }
+        if let Some(view) = self.archive.as_mut() {
+            match action {
+                Action::Quit => self.should_exit = true,
+                Action::Back | Action::Archive => self.archive = None,
+                Action::SelectNext => view.list.state.select_next(),
+                Action::SelectPrevious => view.list.state.select_previous(),
+                Action::SelectFirst => view.list.state.select_first(),
+                Action::SelectLast => view.list.state.select_last(),
+                Action::Restore => self.restore_archived(),
+                _ => {}
+            }
+            return;
+        }
+        if self.board.is_some() && self.handle_board_action(action) {
+            return;
+        }
+        match action {
+            Action::Quit => self.should_exit = true,
+            Action::Back if self.filter.is_active() => self.update_filter(Filter::clear),
+            Action::Back => self.should_exit = true,
+            Action::SelectNone => self.select_none(),
+            Action::SelectNext => self.select_next(),
+            Action::SelectPrevious => self.select_previous(),
+            Action::SelectFirst => self.select_first(),
+            Action::SelectLast => self.select_last(),
+            Action::ToggleStatus => self.toggle_status(),
+            Action::SwitchList => self.switch_todo_history(),
+            Action::Flush => self.confirm_flush(),
+            Action::Add => self.add_todo(),
+            Action::AddSubtask => self.add_subtask(),
+            Action::ToggleCollapse => self.toggle_collapse(),
+            Action::Edit => self.edit_todo(),
+            Action::Delete => self.confirm_delete(),
+            Action::Sort => self.sort_list(),
+            Action::MoveDown => self.move_item(1),
+            Action::MoveUp => self.move_item(-1),
+            Action::PreviousStatus => self.shift_status(-1),
+            Action::NextStatus => self.shift_status(1),
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+            Action::Topics => self.open_topics(),
+            Action::Search => self.filter.typing = true,
+            Action::FilterStatus => self.update_filter(Filter::cycle_status),
+            Action::Stats => {
+                let stats = Stats::new(&self.todo_list.items, &self.history_list.items, Utc::now());
+                self.stats = Some(stats);
+            }
+            Action::Board => self.open_board(),
+            Action::Archive => self.open_archive(),
+            Action::Restore => self.restore_task(),
+            // only bound in the topic panel
+            Action::OpenTopic | Action::NewTopic | Action::RenameTopic | Action::DeleteTopic => {}
+        }
+    }
+
+    fn open_archive(&mut self) {
+        match archive::load(&self.store, &self.topic) {
+            Ok(entries) => {
+                let (months, items) = entries.into_iter().unzip();
+                let mut list = TodoList::new(items);
+                list.state.select_first();
+                self.archive = Some(ArchiveView { months, list });
+            }
+            Err(err) => {
+                self.modal = Some(Modal::error(format!(
+                    "Could not load the archive of '{}': {err}",
+                    self.topic
+                )));
+            }
+        }
+    }
+
+    /// Move the selected task of the history back to the todo list, as a task to do.
+    fn restore_task(&mut self) {
+        if !self.focus_history {
+            return;
+        }
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        self.checkpoint();
+        if let Some(mut item) = tree::remove(&mut self.history_list.items, &path) {
+            item.set_status(Status::Todo);
+            self.todo_list.items.push(item);
+        }
+    }
+
+    /// Move the selected archived task back to the todo list, as a task to do.
+    fn restore_archived(&mut self) {
+        let Some(view) = self.archive.as_mut() else {
+            return;
+        };
+        let Some(index) = view
+            .list
+            .state
+            .selected()
+            .filter(|index| *index < view.list.items.len())
+        else {
+            return;
+        };
+        let month = view.months[index].clone();
+        let item = view.list.items[index].clone();
+        let mut restored = item.clone();
+        restored.set_status(Status::Todo);
+        // archives written before ids were kept unique may hold ids given again since
+        let taken = [&self.todo_list.items, &self.history_list.items]
+            .iter()
+            .any(|items| tree::find(items, &|item| item.id == restored.id).is_some());
+        if taken {
+            restored.id = 0;
+        }
+        self.checkpoint();
+        self.todo_list.items.push(restored);
+        base::assign_ids(
+            &mut self.todo_list.items,
+            &mut self.history_list.items,
+            &mut self.last_id,
+        );
+        let restored_id = self.todo_list.items.last().map(|item| item.id);
+        // the task stays archived until it is saved in the list
+        self.save();
+        if self.save_error.is_some() {
+            // the retries would save it in the list while it is still archived
+            let path = tree::find(&self.todo_list.items, &|item| Some(item.id) == restored_id);
+            if let Some(path) = path {
+                tree::remove(&mut self.todo_list.items, &path);
+            }
+            return;
+        }
+        if let Err(err) = archive::remove(&self.store, &self.topic, &month, &item) {
+            self.modal = Some(Modal::error(format!(
+                "Could not remove '{}' from the archive: {err}",
+                item.todo
+            )));
+            return;
+        }
+        if let Some(view) = self.archive.as_mut() {
+            view.months.remove(index);
+            view.list.items.remove(index);
+        }
+    }
+
+    /// Handle the actions that behave differently on the board, returning whether it did.
+    fn handle_board_action(&mut self, action: Action) -> bool {
+        let Some(board) = self.board.as_mut() else {
+            return false;
+        };
+        match action {
+            Action::Back | Action::Board => self.board = None,
+            Action::SelectNone => board.focus(-1),
+            Action::ToggleStatus => board.focus(1),
+            Action::SelectNext => board.state_mut().select_next(),
+            Action::SelectPrevious => board.state_mut().select_previous(),
+            Action::SelectFirst => board.state_mut().select_first(),
+            Action::SelectLast => board.state_mut().select_last(),
+            // only the todo list is on the board
+            Action::SwitchList | Action::MoveDown | Action::MoveUp | Action::ToggleCollapse => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// Show the todo list as a board, starting on the column of the selected item.
+    fn open_board(&mut self) {
+        self.focus_history = false;
+        let mut board = Board::default();
+        match self.selected_path() {
+            Some(path) => board.select(path[0], &self.todo_list.items, &self.filter),
+            None => board.focus(0),
+        }
+        self.board = Some(board);
+    }
+
+    fn open_topics(&mut self) {
+        // make sure the current topic is listed even before its first save
+        self.save();
+        self.topic_panel = Some(TopicPanel::new(self.store.list_topics(), &self.topic));
+    }
+
+    fn handle_topic_key(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        let Some(panel) = self.topic_panel.as_mut() else {
+            return;
+        };
+        let Some(action) = self.keymap.topic_action(&key) else {
+            return;
+        };
+        match action {
+            Action::Quit | Action::Back | Action::Topics => self.topic_panel = None,
+            Action::SelectNext => panel.state.select_next(),
+            Action::SelectPrevious => panel.state.select_previous(),
+            Action::SelectFirst => panel.state.select_first(),
+            Action::SelectLast => panel.state.select_last(),
+            Action::NewTopic => {
+                self.modal = Some(Modal::prompt(
+                    "New topic",
+                    "Topic",
+                    "",
+                    ModalAction::NewTopic,
+                ));
+            }
+            Action::RenameTopic => {
+                if let Some(topic) = panel.selected() {
+                    let prompt =
+                        Modal::prompt("Rename topic", "Topic", topic, ModalAction::RenameTopic);
+                    self.modal = Some(prompt);
+                }
+            }
+            Action::DeleteTopic => {
+                if let Some(topic) = panel.selected() {
+                    let message = format!("Delete topic '{topic}' and its history?");
+                    self.modal = Some(Modal::confirm(message, ModalAction::DeleteTopic));
+                }
+            }
+            Action::OpenTopic => {
+                if let Some(topic) = panel.selected().cloned() {
+                    self.switch_topic(&topic);
+                    self.topic_panel = None;
+                }
+            }
             _ => {}
         }
     }
 
+    /// Send a key to the open dialog, running its action once accepted.
+    fn handle_modal_key(&mut self, key: KeyEvent) {
+        let Some(modal) = self.modal.as_mut() else {
+            return;
+        };
+        let (action, value) = match modal.on_key(key) {
+            Outcome::Open => return,
+            Outcome::Closed => {
+                self.modal = None;
+                return;
+            }
+            Outcome::Accepted(action, value) => (action, value),
+        };
+        // the action may open another dialog
+        self.modal = None;
+        match action {
+            ModalAction::Delete => self.delete_task(),
+            ModalAction::Flush => self.flush_items(),
+            ModalAction::NewTopic => {
+                if self.check_topic_name(value.trim()) {
+                    self.switch_topic(value.trim());
+                    self.open_topics();
+                }
+            }
+            ModalAction::RenameTopic => {
+                if self.check_topic_name(value.trim()) {
+                    self.rename_topic(value.trim());
+                    self.open_topics();
+                }
+            }
+            ModalAction::DeleteTopic => self.delete_topic(),
+        }
+    }
+
+    /// Whether a new topic can take this name, reporting why it cannot.
+    fn check_topic_name(&mut self, name: &str) -> bool {
+        let error = if !utils::is_valid_topic(name) {
+            format!("Invalid topic name '{name}', use letters, digits, '-' or '_'")
+        } else if self.store.list_topics().iter().any(|topic| topic == name) {
+            format!("Topic '{name}' already exists")
+        } else {
+            return true;
+        };
+        self.modal = Some(Modal::error(error));
+        false
+    }
+
+    fn rename_topic(&mut self, to: &str) {
+        let Some(from) = self
+            .topic_panel
+            .as_ref()
+            .and_then(|p| p.selected().cloned())
+        else {
+            return;
+        };
+        match self.store.rename_topic(&from, to) {
+            Ok(()) if from == self.topic => self.topic = to.to_string(),
+            Ok(()) => {}
+            Err(err) => {
+                self.modal = Some(Modal::error(format!(
+                    "Could not rename topic '{from}': {err}"
+                )));
+            }
+        }
+    }
+
+    fn delete_topic(&mut self) {
+        let Some(topic) = self
+            .topic_panel
+            .as_ref()
+            .and_then(|p| p.selected().cloned())
+        else {
+            return;
+        };
+        if let Err(err) = self.store.delete_topic(&topic) {
+            self.modal = Some(Modal::error(format!(
+                "Could not delete topic '{topic}': {err}"
+            )));
+            return;
+        }
+        if topic == self.topic {
+            // fall back on another topic without saving the deleted one back
+            let next = self
+                .store
+                .list_topics()
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| DEFAULT_TOPIC.to_string());
+            self.open_topic(&next);
+        }
+        self.open_topics();
+    }
+
     fn select_none(&mut self) {
         if self.focus_history {
             self.history_list.state.select(None);
@@ -209,22 +1019,68 @@ impl App {
         self.todo_list.state.select_last();
     }
 
+    fn focused_list(&self) -> &TodoList {
+        if self.focus_history {
+            &self.history_list
+        } else {
+            &self.todo_list
+        }
+    }
+
+    fn focused_list_mut(&mut self) -> &mut TodoList {
+        if self.focus_history {
+            &mut self.history_list
+        } else {
+            &mut self.todo_list
+        }
+    }
+
+    fn selected_path(&self) -> Option<ItemPath> {
+        if let Some(board) = &self.board {
+            let index = board.selected(&self.todo_list.items, &self.filter)?;
+            return Some(vec![index]);
+        }
+        self.focused_list().selected_path(&self.filter)
+    }
+
     /// Changes the status of the selected list item
     fn toggle_status(&mut self) {
-        if self.focus_history {
-            if let Some(i) = self.history_list.state.selected() {
-                self.history_list.items[i].status = match self.history_list.items[i].status {
-                    Status::Completed => Status::Todo,
-                    Status::Todo => Status::InProgress,
-                    Status::InProgress => Status::Completed,
-                }
+        if let Some(path) = self.selected_path() {
+            self.checkpoint();
+            if let Some(item) = tree::get_mut(&mut self.focused_list_mut().items, &path) {
+                item.set_status(item.next_status());
             }
-        } else {
-            if let Some(i) = self.todo_list.state.selected() {
-                self.todo_list.items[i].status = match self.todo_list.items[i].status {
-                    Status::Completed => Status::Todo,
-                    Status::Todo => Status::InProgress,
-                    Status::InProgress => Status::Completed,
+        }
+    }
+
+    /// Set the selected item to the status before or after its own, following the selection on
+    /// the board.
+    fn shift_status(&mut self, offset: isize) {
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        let list = self.focused_list();
+        let Some(status) =
+            tree::get(&list.items, &path).and_then(|item| board::neighbour(item.status, offset))
+        else {
+            return;
+        };
+        self.checkpoint();
+        if let Some(item) = tree::get_mut(&mut self.focused_list_mut().items, &path) {
+            item.set_status(status);
+        }
+        if let Some(board) = self.board.as_mut() {
+            board.select(path[0], &self.todo_list.items, &self.filter);
+        }
+    }
+
+    /// Expand or collapse the children of the selected item.
+    fn toggle_collapse(&mut self) {
+        if let Some(path) = self.selected_path() {
+            if let Some(item) = tree::get_mut(&mut self.focused_list_mut().items, &path) {
+                if !item.children.is_empty() {
+                    item.collapsed = !item.collapsed;
+                    self.dirty = true;
                 }
             }
         }
@@ -234,89 +1090,213 @@ impl App {
         self.focus_history = !self.focus_history;
     }
 
+    /// Ask before moving the completed tasks to the history, and the reopened ones back.
+    fn confirm_flush(&mut self) {
+        let done = self
+            .todo_list
+            .items
+            .iter()
+            .filter(|item| item.is_done())
+            .count();
+        let reopened = self
+            .history_list
+            .items
+            .iter()
+            .filter(|item| !item.is_done())
+            .count();
+        let message = match (done, reopened) {
+            (0, 0) => {
+                self.modal = Some(Modal::info("Nothing to flush"));
+                return;
+            }
+            (_, 0) => format!("Move {done} completed tasks to the history?"),
+            _ => format!(
+                "Move {done} completed tasks to the history, and {reopened} reopened tasks back to the list?"
+            ),
+        };
+        self.modal = Some(Modal::confirm(message, ModalAction::Flush));
+    }
+
     fn flush_items(&mut self) {
-        let mut done_items = TodoList {
-            items: self
-                .history_list
-                .items
-                .iter()
-                .filter(|item| item.status == Status::Completed)
-                .cloned()
-                .collect::<Vec<_>>(),
-            state: self.history_list.state.clone(),
-        };
-        done_items.items.extend(
-            self.todo_list
-                .items
-                .iter()
-                .filter(|item| item.status == Status::Completed)
-                .cloned()
-                .collect::<Vec<_>>(),
+        self.checkpoint();
+        let todo = std::mem::take(&mut self.todo_list.items);
+        let history = std::mem::take(&mut self.history_list.items);
+        let today = chrono::Local::now().date_naive();
+        (self.todo_list.items, self.history_list.items) = base::flush(todo, history, today);
+        base::assign_ids(
+            &mut self.todo_list.items,
+            &mut self.history_list.items,
+            &mut self.last_id,
         );
-        let mut other_items = TodoList {
-            items: self
-                .history_list
-                .items
-                .iter()
-                .filter(|item| item.status != Status::Completed)
-                .cloned()
-                .collect(),
-            state: self.todo_list.state.clone(),
-        };
-        other_items.items.extend(
-            self.todo_list
-                .items
-                .iter()
-                .filter(|item| item.status != Status::Completed)
-                .cloned()
-                .collect::<Vec<_>>(),
-        );
-        self.todo_list = other_items;
-        self.history_list = done_items;
     }
 
     fn add_todo(&mut self) {
         self.popup_mode = !self.popup_mode;
     }
 
-    fn delete_task(&mut self) {
-        if self.focus_history {
-            if let Some(i) = self.history_list.state.selected() {
-                self.history_list.items = self
-                    .history_list
-                    .items
-                    .iter()
-                    .enumerate()
-                    .filter(|&(j, _)| j != i)
-                    .map(|(_, todo)| todo.clone())
-                    .collect();
-            }
+    /// Open the form to add a child to the selected item of the focused list.
+    fn add_subtask(&mut self) {
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        if let Some(item) = tree::get(&self.focused_list().items, &path) {
+            self.form_target = FormTarget::Child(item.id);
+            self.popup_mode = true;
+        }
+    }
+
+    /// Open the form prefilled with the selected item of the focused list.
+    fn edit_todo(&mut self) {
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        let list = if self.focus_history {
+            &self.history_list
         } else {
-            if let Some(i) = self.todo_list.state.selected() {
-                self.todo_list.items = self
-                    .todo_list
-                    .items
-                    .iter()
-                    .enumerate()
-                    .filter(|&(j, _)| j != i)
-                    .map(|(_, todo)| todo.clone())
-                    .collect();
+            &self.todo_list
+        };
+        if let Some(item) = tree::get(&list.items, &path) {
+            self.todo_form.load(item);
+            self.form_target = FormTarget::Edit(item.id);
+            self.popup_mode = true;
+        }
+    }
+
+    /// Get the form values and either add them as a new task or write them back to the
+    /// edited item.
+    fn submit_form(&mut self) {
+        self.popup_mode = false;
+        self.checkpoint();
+        match std::mem::replace(&mut self.form_target, FormTarget::New) {
+            FormTarget::Edit(id) => {
+                let mut form = std::mem::take(&mut self.todo_form);
+                match self.find_item_mut(id) {
+                    Some(item) => form.apply(item),
+                    None => {
+                        form.clear();
+                        self.warning = Some("The task was deleted on disk meanwhile".to_string());
+                    }
+                }
+                self.todo_form = form;
+            }
+            FormTarget::Child(id) => {
+                let todo = self.todo_form.extract();
+                match self.find_item_mut(id) {
+                    Some(parent) => {
+                        parent.children.push(todo);
+                        parent.collapsed = false;
+                    }
+                    None => {
+                        self.warning =
+                            Some("The parent task was deleted on disk meanwhile".to_string());
+                    }
+                }
+            }
+            FormTarget::New => {
+                let todo = self.todo_form.extract();
+                self.todo_list.items.push(todo);
             }
         }
+        base::assign_ids(
+            &mut self.todo_list.items,
+            &mut self.history_list.items,
+            &mut self.last_id,
+        );
     }
 
+    /// Item with this id in either list.
+    fn find_item_mut(&mut self, id: u64) -> Option<&mut TodoItem> {
+        for list in [&mut self.todo_list, &mut self.history_list] {
+            if let Some(path) = tree::find(&list.items, &|item| item.id == id) {
+                return tree::get_mut(&mut list.items, &path);
+            }
+        }
+        None
+    }
+
+    /// Ask before deleting the selected item and its subtasks.
+    fn confirm_delete(&mut self) {
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        let Some(item) = tree::get(&self.focused_list().items, &path) else {
+            return;
+        };
+        let mut subtasks = 0;
+        tree::for_each(&item.children, &mut |_| subtasks += 1);
+        let message = match subtasks {
+            0 => format!("Delete '{}'?", item.todo),
+            _ => format!("Delete '{}' and its {subtasks} subtasks?", item.todo),
+        };
+        self.modal = Some(Modal::confirm(message, ModalAction::Delete));
+    }
+
+    fn delete_task(&mut self) {
+        if let Some(path) = self.selected_path() {
+            self.checkpoint();
+            tree::remove(&mut self.focused_list_mut().items, &path);
+        }
+    }
+
+    /// Sort the focused list in the mode following its current one, keeping the selection.
     fn sort_list(&mut self) {
-        // sort the main list by ascending name
-        self.todo_list.items.sort_by(|a, b| a.todo.cmp(&b.todo));
+        self.checkpoint();
+        let filter = &self.filter;
+        let list = if self.focus_history {
+            &mut self.history_list
+        } else {
+            &mut self.todo_list
+        };
+        let selected = list.selected_id(filter);
+        let mode = list
+            .sort_mode
+            .map_or_else(SortMode::default, SortMode::next);
+        mode.sort(&mut list.items);
+        list.sort_mode = Some(mode);
+        list.select_id(selected, filter);
+    }
+
+    /// Swap the selected item with its previous or next sibling.
+    fn move_item(&mut self, offset: isize) {
+        let Some(mut path) = self.selected_path() else {
+            return;
+        };
+        let Some(index) = path.pop() else {
+            return;
+        };
+        let Some(target) = index.checked_add_signed(offset) else {
+            return;
+        };
+        let list = self.focused_list();
+        let Some(siblings) = tree::children(&list.items, &path) else {
+            return;
+        };
+        if target >= siblings.len() {
+            return;
+        }
+        self.checkpoint();
+        let filter = &self.filter;
+        let list = if self.focus_history {
+            &mut self.history_list
+        } else {
+            &mut self.todo_list
+        };
+        if let Some(siblings) = tree::children_mut(&mut list.items, &path) {
+            siblings.swap(index, target);
+        }
+        list.sort_mode = None;
+        path.push(target);
+        list.select_path(Some(path), filter);
     }
 }
 
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        self.cursor = None;
         let [header_area, main_area, footer_area] = Layout::vertical([
             Constraint::Length(2),
             Constraint::Fill(1),
-            Constraint::Length(1),
+            Constraint::Length(2),
         ])
         .areas(area);
 
@@ -327,71 +1307,204 @@ impl Widget for &mut App {
         ])
         .areas(main_area);
 
-        App::render_header(header_area, buf);
-        App::render_footer(footer_area, buf);
-        self.render_list(list_area, buf);
-        self.render_selected_item(item_area, buf);
-        self.render_history(history_area, buf);
+        self.render_header(header_area, buf);
+        self.render_footer(footer_area, buf);
+        if let Some(stats) = &self.stats {
+            stats.render(main_area, buf);
+        } else if self.archive.is_some() {
+            self.render_archive(main_area, buf);
+        } else if self.board.is_some() {
+            let [board_area, item_area] =
+                Layout::vertical([Constraint::Fill(3), Constraint::Fill(1)]).areas(main_area);
+            self.render_board(board_area, buf);
+            self.render_selected_item(item_area, buf);
+        } else {
+            self.render_list(list_area, buf);
+            self.render_selected_item(item_area, buf);
+            self.render_history(history_area, buf);
+        }
 
         // pop up
         if self.popup_mode {
             self.render_popup(area, buf);
         }
+        if self.topic_panel.is_some() {
+            self.render_topics(area, buf);
+        }
+        if let Some(modal) = &self.modal {
+            self.cursor = modal.render(area, buf);
+        }
     }
 }
 
 /// Rendering logic for the app
 impl App {
-    fn render_header(area: Rect, buf: &mut Buffer) {
-        Paragraph::new("TODO").bold().centered().render(area, buf);
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let [title, store] = Layout::vertical([Constraint::Length(1); 2]).areas(area);
+        Paragraph::new(format!("TODO - {}", self.topic))
+            .bold()
+            .centered()
+            .render(title, buf);
+        Paragraph::new(self.store.root().display().to_string())
+            .fg(theme().muted_fg)
+            .centered()
+            .render(store, buf);
     }
 
-    fn render_footer(area: Rect, buf: &mut Buffer) {
-        Paragraph::new("Use ↓↑ to move, ← to unselect, → to change status, g/G to go top/bottom, a to add a task, f to flush, d to delete.")
-            .centered()
-            .render(area, buf);
+    fn render_footer(&mut self, area: Rect, buf: &mut Buffer) {
+        if let Some(err) = &self.save_error {
+            Paragraph::new(err.as_str())
+                .fg(theme().error_fg)
+                .centered()
+                .render(area, buf);
+            return;
+        }
+        if let Some(warning) = &self.warning {
+            Paragraph::new(warning.as_str())
+                .fg(theme().overdue_fg)
+                .centered()
+                .render(area, buf);
+            return;
+        }
+        let mut help_area = area;
+        if self.filter.typing || self.filter.is_active() {
+            let [search_area, rest] = Layout::vertical([Constraint::Length(1); 2]).areas(area);
+            self.render_search(search_area, buf);
+            help_area = rest;
+        }
+        let help: Vec<Line> = if self.filter.typing {
+            // the search field reads these keys itself
+            vec![Line::from("Enter keep, Esc clear")]
+        } else {
+            let help =
+                self.keymap
+                    .help(self.view(), help_area.width.into(), help_area.height.into());
+            help.into_iter().map(Line::from).collect()
+        };
+        Paragraph::new(help).centered().render(help_area, buf);
+    }
+
+    /// Screen shown instead of the lists, if any, whose actions are listed in the footer.
+    fn view(&self) -> View {
+        if self.stats.is_some() {
+            View::Stats
+        } else if self.archive.is_some() {
+            View::Archive
+        } else if self.board.is_some() {
+            View::Board
+        } else if self.filter.is_active() {
+            View::Search
+        } else {
+            View::Lists
+        }
+    }
+
+    fn render_search(&mut self, area: Rect, buf: &mut Buffer) {
+        let status = match self.filter.status {
+            Some(Status::Todo) => " [TODO]",
+            Some(Status::InProgress) => " [IN PROGRESS]",
+            Some(Status::Completed) => " [DONE]",
+            None => "",
+        };
+        let [query_area, status_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(status.len() as u16 + 1),
+        ])
+        .areas(area);
+        self.filter.query.render(query_area, buf);
+        Paragraph::new(status)
+            .fg(theme().in_progress_fg)
+            .render(status_area, buf);
+        if self.filter.typing {
+            self.cursor = Some(self.filter.query.cursor_position(query_area));
+        }
     }
 
     fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
         let block = Block::new()
-            .title(Line::raw("List").centered())
+            .title(Line::raw(list_title("List", &self.todo_list)).centered())
             .borders(Borders::TOP)
             .border_set(symbols::border::EMPTY)
-            .border_style(TODO_HEADER_STYLE)
-            .bg(NORMAL_ROW_BG);
+            .border_style(theme().header_style())
+            .bg(theme().row_bg);
 
         // Iterate through all elements in the `items` and stylize them.
         let items: Vec<ListItem> = self
             .todo_list
-            .items
+            .visible(&self.filter)
             .iter()
             .enumerate()
-            .map(|(i, todo_item)| {
+            .map(|(i, path)| {
                 let color = alternate_colors(i);
-                ListItem::from(todo_item).bg(color)
+                tree_row(&self.todo_list.items, path).bg(color)
             })
             .collect();
 
+        let inner = block.inner(area);
         // Create a List from all list items and highlight the currently selected one
         let list = List::new(items)
             .block(block)
-            .highlight_style(SELECTED_STYLE)
+            .highlight_style(theme().selected_style())
             .highlight_symbol(">")
             .highlight_spacing(HighlightSpacing::Always);
 
         // We need to disambiguate this trait method as both `Widget` and `StatefulWidget` share the
         // same method name `render`.
+        self.todo_list.area = inner;
         StatefulWidget::render(list, area, buf, &mut self.todo_list.state);
     }
 
     fn render_selected_item(&self, area: Rect, buf: &mut Buffer) {
         // We get the info depending on the item's state.
-        let info = if let Some(i) = self.todo_list.state.selected() {
-            match self.todo_list.items[i].status {
-                Status::Completed => format!("✓ DONE: {}", self.todo_list.items[i].info),
-                Status::InProgress => format!("✍ IN PROGRESS : {}", self.todo_list.items[i].info),
-                Status::Todo => format!("☐ TODO: {}", self.todo_list.items[i].info),
+        let selected_path = match &self.board {
+            Some(_) => self.selected_path(),
+            None => self.todo_list.selected_path(&self.filter),
+        };
+        let selected = selected_path.and_then(|path| tree::get(&self.todo_list.items, &path));
+        let info = if let Some(item) = selected {
+            let mut info = match item.status {
+                Status::Completed => format!("✓ DONE: {}", item.info),
+                Status::InProgress => format!("✍ IN PROGRESS : {}", item.info),
+                Status::Todo => format!("☐ TODO: {}", item.info),
+            };
+            if let Some((completed, total)) = item.progress() {
+                info.push_str(&format!("\nSubtasks: {completed}/{total} completed"));
+            }
+            if let Some(priority) = item.priority {
+                info.push_str(&format!("\nPriority: {priority}"));
+            }
+            if let Some(due) = item.due {
+                info.push_str(&format!("\nDue: {}", due.format(form::DATE_FORMAT)));
+            }
+            if let Some(recurrence) = &item.recurrence {
+                info.push_str(&format!("\nRepeats: {recurrence}"));
+            }
+            if !item.tags.is_empty() {
+                info.push_str(&format!("\nTags: {}", item.tags.join(", ")));
+            }
+            let now = Utc::now();
+            if !item.sessions.is_empty() {
+                let spent = base::format_duration(item.time_spent(None, now));
+                match item.running_since() {
+                    Some(start) => info.push_str(&format!(
+                        "\n⏱ Running for {}, {spent} in total",
+                        base::format_duration(now - start)
+                    )),
+                    None => info.push_str(&format!("\nTime spent: {spent}")),
+                }
             }
+            info.push_str(&format!(
+                "\n#{}, created {} ago",
+                item.id,
+                base::format_age(item.created_at, now)
+            ));
+            if let Some(completed_at) = item.completed_at {
+                info.push_str(&format!(
+                    ", completed {} ago",
+                    base::format_age(completed_at, now)
+                ));
+            }
+            info
         } else {
             "Nothing selected...".to_string()
         };
@@ -401,14 +1514,14 @@ impl App {
             .title(Line::raw("Description").centered())
             .borders(Borders::TOP)
             .border_set(symbols::border::EMPTY)
-            .border_style(TODO_HEADER_STYLE)
-            .bg(NORMAL_ROW_BG)
+            .border_style(theme().header_style())
+            .bg(theme().row_bg)
             .padding(Padding::horizontal(1));
 
         // We can now render the item info
         Paragraph::new(info)
             .block(block)
-            .fg(TEXT_FG_COLOR)
+            .fg(theme().text_fg)
             .wrap(Wrap { trim: false })
             .render(area, buf);
     }
@@ -416,42 +1529,125 @@ impl App {
     fn render_history(&mut self, area: Rect, buf: &mut Buffer) {
         // We show the list item's info under the list in this paragraph
         let block = Block::new()
-            .title(Line::raw("History").centered())
+            .title(Line::raw(list_title("History", &self.history_list)).centered())
             .borders(Borders::TOP)
             .border_set(symbols::border::EMPTY)
-            .border_style(TODO_HEADER_STYLE)
-            .bg(NORMAL_ROW_BG)
+            .border_style(theme().header_style())
+            .bg(theme().row_bg)
             .padding(Padding::horizontal(1));
 
         let items: Vec<ListItem> = self
             .history_list
-            .items
+            .visible(&self.filter)
             .iter()
             .enumerate()
-            .map(|(i, todo_item)| {
+            .map(|(i, path)| {
                 let color = alternate_colors(i);
-                ListItem::from(todo_item).bg(color)
+                tree_row(&self.history_list.items, path).bg(color)
             })
             .collect();
 
+        let inner = block.inner(area);
         // Create a List from all list items and highlight the currently selected one
         let list = List::new(items)
             .block(block)
-            .highlight_style(SELECTED_STYLE)
+            .highlight_style(theme().selected_style())
             .highlight_symbol(">")
             .highlight_spacing(HighlightSpacing::Always);
 
         // We need to disambiguate this trait method as both `Widget` and `StatefulWidget` share the
         // same method name `render`.
+        self.history_list.area = inner;
         StatefulWidget::render(list, area, buf, &mut self.history_list.state);
     }
 
+    /// Render a column per status with the cards of the todo list, highlighting the selected
+    /// card of the focused column.
+    fn render_board(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(board) = self.board.as_mut() else {
+            return;
+        };
+        let areas = Layout::horizontal([Constraint::Fill(1); board::COLUMNS.len()]).split(area);
+        for (column, status) in board::COLUMNS.into_iter().enumerate() {
+            let cards = Board::cards(&self.todo_list.items, status, &self.filter);
+            let name = match status {
+                Status::Todo => "To do",
+                Status::InProgress => "In progress",
+                Status::Completed => "Done",
+            };
+            let focused = column == board.column;
+            let title = Line::raw(format!("{name} ({})", cards.len())).centered();
+            let block = Block::new()
+                .title(if focused { title.bold() } else { title })
+                .borders(if column + 1 < board::COLUMNS.len() {
+                    Borders::TOP | Borders::RIGHT
+                } else {
+                    Borders::TOP
+                })
+                .border_style(theme().header_style())
+                .bg(theme().row_bg);
+            let items: Vec<ListItem> = cards
+                .iter()
+                .enumerate()
+                .map(|(i, index)| {
+                    ListItem::from(&self.todo_list.items[*index]).bg(alternate_colors(i))
+                })
+                .collect();
+            let highlight_style = if focused {
+                theme().selected_style()
+            } else {
+                Style::new()
+            };
+            let list = List::new(items)
+                .block(block)
+                .highlight_style(highlight_style)
+                .highlight_symbol(">")
+                .highlight_spacing(HighlightSpacing::Always);
+            StatefulWidget::render(list, areas[column], buf, &mut board.states[column]);
+        }
+    }
+
+    fn render_archive(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(view) = self.archive.as_mut() else {
+            return;
+        };
+        let block = Block::new()
+            .title(Line::raw(format!("Archive of {}", self.topic)).centered())
+            .borders(Borders::TOP)
+            .border_set(symbols::border::EMPTY)
+            .border_style(theme().header_style())
+            .bg(theme().row_bg);
+        let items: Vec<ListItem> = view
+            .list
+            .items
+            .iter()
+            .zip(&view.months)
+            .enumerate()
+            .map(|(i, (item, month))| {
+                let mut line = item_line(item);
+                line.spans
+                    .insert(0, Span::styled(format!(" {month}"), theme().muted_fg));
+                ListItem::new(line).bg(alternate_colors(i))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme().selected_style())
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, area, buf, &mut view.list.state);
+    }
+
     fn render_popup(&mut self, area: Rect, buf: &mut Buffer) {
-        let block = Block::bordered().style(TODO_HEADER_STYLE);
-        let popup_area = popup::popup_area(area, 60, 20);
+        let block = Block::bordered().style(theme().header_style());
+        let popup_area = popup::popup_area(area, 60, 50);
         let vertical = Layout::vertical([Constraint::Percentage(20), Constraint::Percentage(80)]);
         let [instructions, content] = vertical.areas(popup_area);
-        let text = "New task";
+        let text = match self.form_target {
+            FormTarget::New => "New task",
+            FormTarget::Child(_) => "New subtask",
+            FormTarget::Edit(_) => "Edit task",
+        };
         let paragraph = Paragraph::new(text.slow_blink())
             .centered()
             .wrap(Wrap { trim: true });
@@ -459,28 +1655,424 @@ impl App {
         block.render(popup_area, buf);
         paragraph.render(instructions, buf);
         self.todo_form.render(content, buf);
+        self.form_area = content;
+        self.cursor = Some(self.todo_form.cursor_position(content));
+    }
+
+    fn render_topics(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(panel) = self.topic_panel.as_mut() else {
+            return;
+        };
+        let block = Block::bordered()
+            .title(Line::raw("Topics").centered())
+            .style(theme().header_style());
+        let popup_area = popup::popup_area(area, 40, 50);
+        let content = block.inner(popup_area);
+        Clear.render(popup_area, buf);
+        block.render(popup_area, buf);
+        let help = self.keymap.topic_help();
+        panel.render(content, buf, &self.topic, &help, theme().selected_style());
+    }
+}
+
+fn list_title(name: &str, list: &TodoList) -> String {
+    match list.sort_mode {
+        Some(mode) => format!("{name} (by {mode})"),
+        None => name.to_string(),
     }
 }
 
-const fn alternate_colors(i: usize) -> Color {
-    if i % 2 == 0 {
-        NORMAL_ROW_BG
+fn alternate_colors(i: usize) -> Color {
+    if i.is_multiple_of(2) {
+        theme().row_bg
+    } else {
+        theme().alt_row_bg
+    }
+}
+
+/// Pick a stable chip color for a tag from its name.
+fn tag_color(tag: &str) -> Color {
+    let hash = tag
+        .bytes()
+        .fold(0usize, |acc, b| acc.wrapping_add(b as usize));
+    let colors = &theme().tag_bg;
+    colors[hash % colors.len()]
+}
+
+/// Render the row of a nested item, indented by its depth with a marker for its children.
+fn tree_row<'a>(items: &[TodoItem], path: &[usize]) -> ListItem<'a> {
+    let Some(item) = tree::get(items, path) else {
+        return ListItem::new("");
+    };
+    let marker = match (item.children.is_empty(), item.collapsed) {
+        (true, _) => "  ",
+        (false, true) => "▸ ",
+        (false, false) => "▾ ",
+    };
+    let mut line = item_line(item);
+    let indent = "  ".repeat(path.len() - 1);
+    line.spans.insert(0, Span::raw(format!("{indent}{marker}")));
+    ListItem::new(line)
+}
+
+fn item_line<'a>(value: &TodoItem) -> Line<'a> {
+    let overdue = value.is_overdue(chrono::Local::now().date_naive());
+    let mut spans = vec![match value.status {
+        Status::Todo => Span::styled(format!(" ☐ {}", value.todo), theme().text_fg),
+        Status::Completed => Span::styled(format!(" ✓ {}", value.todo), theme().completed_fg),
+        Status::InProgress => Span::styled(format!(" ✍ {}", value.todo), theme().in_progress_fg),
+    }];
+    if let Some((completed, total)) = value.progress() {
+        spans.push(Span::styled(
+            format!(" {completed}/{total}"),
+            theme().muted_fg,
+        ));
+    }
+    if let Some(priority) = value.priority {
+        spans.push(Span::styled(format!(" [{priority}]"), theme().muted_fg));
+    }
+    if let Some(due) = value.due {
+        let color = if overdue {
+            theme().overdue_fg
+        } else {
+            theme().muted_fg
+        };
+        spans.push(Span::styled(format!(" due {due}"), color));
+    }
+    if value.recurrence.is_some() {
+        spans.push(Span::styled(" ↻", theme().muted_fg));
+    }
+    // total time spent on the tasks once done, as in the history
+    if value.status == Status::Completed && !value.sessions.is_empty() {
+        let spent = value.time_spent(None, Utc::now());
+        spans.push(Span::styled(
+            format!(" ⏱ {}", base::format_duration(spent)),
+            theme().muted_fg,
+        ));
+    }
+    for tag in &value.tags {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(format!(" {tag} "), theme().text_fg).bg(tag_color(tag)));
+    }
+    let line = Line::from(spans);
+    if overdue {
+        line.add_modifier(Modifier::BOLD)
     } else {
-        ALT_ROW_BG_COLOR
+        line
     }
 }
 
 impl From<&TodoItem> for ListItem<'_> {
     fn from(value: &TodoItem) -> Self {
-        let line = match value.status {
-            Status::Todo => Line::styled(format!(" ☐ {}", value.todo), TEXT_FG_COLOR),
-            Status::Completed => {
-                Line::styled(format!(" ✓ {}", value.todo), COMPLETED_TEXT_FG_COLOR)
-            }
-            Status::InProgress => {
-                Line::styled(format!(" ✍ {}", value.todo), IN_PROGRESS_TEXT_FG_COLOR)
-            }
+        ListItem::new(item_line(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::TempFolder;
+
+    fn test_app() -> App {
+        let todo_list = TodoList::from_iter([
+            (Status::Todo, "b", "second"),
+            (Status::Completed, "a", "first"),
+            (Status::InProgress, "c", "third"),
+        ]);
+        let history_list = TodoList::from_iter([(Status::Completed, "old", "done")]);
+        let store = Store::new(std::env::temp_dir().join("todo-test"));
+        App::with_lists(store, "test", todo_list, history_list, 0)
+    }
+
+    fn names(list: &TodoList) -> Vec<&str> {
+        list.items.iter().map(|item| item.todo.as_str()).collect()
+    }
+
+    #[test]
+    fn test_undo_redo_mutations() {
+        let mut app = test_app();
+
+        app.todo_list.state.select(Some(0));
+        app.toggle_status();
+        assert_eq!(app.todo_list.items[0].status, Status::InProgress);
+        app.sort_list();
+        assert_eq!(names(&app.todo_list), ["a", "b", "c"]);
+        app.flush_items();
+        assert_eq!(names(&app.todo_list), ["b", "c"]);
+        assert_eq!(names(&app.history_list), ["old", "a"]);
+        app.focus_history = true;
+        app.history_list.state.select(Some(0));
+        app.delete_task();
+        assert_eq!(names(&app.history_list), ["a"]);
+
+        app.undo();
+        assert_eq!(names(&app.history_list), ["old", "a"]);
+        app.undo();
+        assert_eq!(names(&app.todo_list), ["a", "b", "c"]);
+        assert_eq!(names(&app.history_list), ["old"]);
+        app.undo();
+        assert_eq!(names(&app.todo_list), ["b", "a", "c"]);
+        app.undo();
+        assert_eq!(app.todo_list.items[0].status, Status::Todo);
+        // nothing left to undo
+        app.undo();
+        assert_eq!(app.todo_list.items[0].status, Status::Todo);
+
+        app.redo();
+        app.redo();
+        assert_eq!(names(&app.todo_list), ["a", "b", "c"]);
+        assert_eq!(app.todo_list.items[1].status, Status::InProgress);
+
+        // a new mutation drops the redo entries
+        app.focus_history = false;
+        app.todo_list.state.select(Some(0));
+        app.delete_task();
+        app.redo();
+        assert_eq!(names(&app.todo_list), ["b", "c"]);
+    }
+
+    #[test]
+    fn test_undo_form_submission() {
+        let mut app = test_app();
+        app.add_todo();
+        app.todo_form.on_paste("new task");
+        app.submit_form();
+        assert_eq!(names(&app.todo_list), ["b", "a", "c", "new task"]);
+
+        app.todo_list.state.select(Some(1));
+        app.edit_todo();
+        app.todo_form.on_paste(" renamed");
+        app.submit_form();
+        assert_eq!(app.todo_list.items[1].todo, "a renamed");
+
+        app.undo();
+        assert_eq!(app.todo_list.items[1].todo, "a");
+        app.undo();
+        assert_eq!(names(&app.todo_list), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_filter_keeps_selection_on_items() {
+        let mut app = test_app();
+        app.todo_list.state.select(Some(2));
+        // only "c" is described with "third"
+        app.update_filter(|filter| filter.query.set_value("thi"));
+        assert_eq!(app.todo_list.state.selected(), Some(0));
+        app.toggle_status();
+        assert_eq!(app.todo_list.items[2].status, Status::Completed);
+        assert_eq!(app.todo_list.items[0].status, Status::Todo);
+
+        // hidden items are unselected, and visible ones keep their selection
+        app.todo_list.state.select(None);
+        app.update_filter(Filter::clear);
+        app.todo_list.state.select(Some(1));
+        app.update_filter(Filter::cycle_status);
+        assert_eq!(app.todo_list.state.selected(), None);
+        app.todo_list.state.select(Some(0));
+        app.update_filter(Filter::clear);
+        assert_eq!(app.todo_list.state.selected(), Some(0));
+        app.toggle_status();
+        assert_eq!(app.todo_list.items[0].status, Status::InProgress);
+    }
+
+    #[test]
+    fn test_sort_and_move_keep_selection() {
+        let mut app = test_app();
+        app.todo_list.state.select(Some(0));
+        app.sort_list();
+        assert_eq!(names(&app.todo_list), ["a", "b", "c"]);
+        assert_eq!(app.todo_list.state.selected(), Some(1));
+        app.sort_list();
+        assert_eq!(names(&app.todo_list), ["c", "b", "a"]);
+
+        app.move_item(-1);
+        assert_eq!(names(&app.todo_list), ["b", "c", "a"]);
+        assert_eq!(app.todo_list.state.selected(), Some(0));
+        assert_eq!(app.todo_list.sort_mode, None);
+        // the first item cannot move further up
+        app.move_item(-1);
+        assert_eq!(names(&app.todo_list), ["b", "c", "a"]);
+
+        // sorting applies to the focused list
+        app.focus_history = true;
+        app.sort_list();
+        assert_eq!(names(&app.todo_list), ["b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_flush_recurring_task() {
+        let mut app = test_app();
+        app.todo_list.items[1].recurrence = "weekly".parse().ok();
+        app.flush_items();
+        assert_eq!(names(&app.todo_list), ["b", "c", "a"]);
+        assert_eq!(names(&app.history_list), ["old", "a"]);
+        let next = &app.todo_list.items[2];
+        assert_eq!(next.status, Status::Todo);
+        assert!(next.due > Some(chrono::Local::now().date_naive()));
+        assert_ne!(next.id, app.history_list.items[1].id);
+    }
+
+    #[test]
+    fn test_mouse_selection() {
+        let mut app = test_app();
+        app.todo_list.area = Rect::new(0, 1, 40, 5);
+        app.history_list.area = Rect::new(0, 10, 40, 5);
+        let click = |row| MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 3,
+            row,
+            modifiers: event::KeyModifiers::NONE,
         };
-        ListItem::new(line)
+
+        app.handle_mouse(click(2));
+        assert_eq!(app.todo_list.state.selected(), Some(1));
+        // rows past the last item select nothing
+        app.handle_mouse(click(5));
+        assert_eq!(app.todo_list.state.selected(), Some(1));
+
+        app.handle_mouse(click(10));
+        assert!(app.focus_history);
+        assert_eq!(app.history_list.state.selected(), Some(0));
+        app.handle_mouse(click(10));
+        assert_eq!(app.history_list.items[0].status, Status::Todo);
+
+        app.handle_mouse(MouseEvent {
+            kind: MouseEventKind::ScrollDown,
+            ..click(1)
+        });
+        assert!(!app.focus_history);
+        assert_eq!(app.todo_list.state.selected(), Some(2));
+    }
+
+    #[test]
+    fn test_board_moves_cards() {
+        let mut app = test_app();
+        app.todo_list.state.select(Some(0));
+        app.open_board();
+        let board = app.board.as_ref().unwrap();
+        assert_eq!(board::COLUMNS[board.column], Status::Todo);
+
+        app.shift_status(1);
+        assert_eq!(app.todo_list.items[0].status, Status::InProgress);
+        // the selection follows the card to its new column
+        let board = app.board.as_ref().unwrap();
+        assert_eq!(board::COLUMNS[board.column], Status::InProgress);
+        assert_eq!(board.states[board.column].selected(), Some(0));
+        assert_eq!(app.selected_path(), Some(vec![0]));
+
+        app.handle_board_action(Action::SelectNext);
+        assert_eq!(app.selected_path(), Some(vec![2]));
+        app.handle_board_action(Action::SelectNone);
+        // the column on the left is now empty
+        assert_eq!(app.selected_path(), None);
+        app.shift_status(-1);
+        assert_eq!(app.todo_list.items[0].status, Status::InProgress);
+
+        app.undo();
+        assert_eq!(app.todo_list.items[0].status, Status::Todo);
+    }
+
+    #[test]
+    fn test_save_merges_external_changes() {
+        let folder = TempFolder::new("reload");
+        let other = folder.store();
+        let mut items = vec![TodoItem::new(Status::Todo, "task", "")];
+        items[0].id = 1;
+        other.save_list(&items, "test.json").unwrap();
+        let mut app = App::new(folder.store(), "test");
+        app.todo_list.items[0].todo = "renamed here".to_string();
+        app.todo_list.state.select(Some(0));
+        app.dirty = true;
+
+        // another process adds a task in the meantime
+        let mut added = TodoItem::new(Status::Todo, "added outside", "");
+        added.id = 2;
+        items.insert(0, added);
+        other.save_list(&items, "test.json").unwrap();
+
+        app.save();
+        let saved = other.load_todo_items("test.json").unwrap();
+        let saved: Vec<&str> = saved.iter().map(|item| item.todo.as_str()).collect();
+        assert_eq!(saved, ["added outside", "renamed here"]);
+        // the selection follows the task
+        assert_eq!(app.todo_list.state.selected(), Some(1));
+        assert!(app.warning.is_none());
+    }
+
+    #[test]
+    fn test_undo_keeps_external_changes() {
+        let folder = TempFolder::new("reload-undo");
+        let other = folder.store();
+        let mut items = vec![
+            TodoItem::new(Status::Todo, "A", ""),
+            TodoItem::new(Status::Todo, "B", ""),
+        ];
+        items[0].id = 1;
+        items[1].id = 2;
+        other.save_list(&items, "test.json").unwrap();
+        let mut app = App::new(folder.store(), "test");
+        app.todo_list.state.select(Some(0));
+        app.toggle_status();
+        app.save();
+
+        let mut added = TodoItem::new(Status::Todo, "C external", "");
+        added.id = 3;
+        let mut items = other.load_todo_items("test.json").unwrap();
+        items.push(added);
+        other.save_list(&items, "test.json").unwrap();
+        app.reload().unwrap();
+        // the toggle can no longer be undone without losing the new task
+        app.undo();
+        app.save();
+        let saved = other.load_todo_items("test.json").unwrap();
+        let saved: Vec<&str> = saved.iter().map(|item| item.todo.as_str()).collect();
+        assert_eq!(saved, ["A", "B", "C external"]);
+        assert_eq!(app.todo_list.items[0].status, Status::InProgress);
+    }
+
+    #[test]
+    fn test_form_follows_task_across_reload() {
+        let folder = TempFolder::new("form");
+        let other = folder.store();
+        let mut items = vec![
+            TodoItem::new(Status::Todo, "A", ""),
+            TodoItem::new(Status::Todo, "B", ""),
+        ];
+        items[0].id = 1;
+        items[1].id = 2;
+        other.save_list(&items, "test.json").unwrap();
+        let mut app = App::new(folder.store(), "test");
+        app.todo_list.state.select(Some(1));
+        app.edit_todo();
+
+        // another process inserts a task on top while the form is open
+        let mut added = TodoItem::new(Status::Todo, "C", "");
+        added.id = 3;
+        items.insert(0, added);
+        other.save_list(&items, "test.json").unwrap();
+        app.reload().unwrap();
+
+        app.todo_form.on_paste(" edited");
+        app.submit_form();
+        app.save();
+        let saved = other.load_todo_items("test.json").unwrap();
+        let saved: Vec<&str> = saved.iter().map(|item| item.todo.as_str()).collect();
+        assert_eq!(saved, ["C", "A", "B edited"]);
+    }
+
+    #[test]
+    fn test_undo_stack_is_bounded() {
+        let mut app = test_app();
+        app.undo = UndoStack::new(2);
+        app.todo_list.state.select(Some(0));
+        for _ in 0..3 {
+            app.toggle_status();
+        }
+        assert_eq!(app.todo_list.items[0].status, Status::Todo);
+        for _ in 0..3 {
+            app.undo();
+        }
+        // only the two latest toggles are undone
+        assert_eq!(app.todo_list.items[0].status, Status::InProgress);
     }
 }
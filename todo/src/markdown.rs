@@ -0,0 +1,158 @@
+use crate::base::{Status, TodoItem};
+use crate::tree::{self, ItemPath};
+
+/// Marker written between the brackets of in progress tasks, by default.
+pub const IN_PROGRESS_MARKER: char = '~';
+
+/// Write the items as a GitHub flavored Markdown checklist.
+///
+/// Subtasks are indented under their parent, and the description follows the task as indented
+/// lines.
+pub fn export(items: &[TodoItem], in_progress: char) -> String {
+    let mut out = String::new();
+    write_items(&mut out, items, in_progress, 0);
+    out
+}
+
+fn write_items(out: &mut String, items: &[TodoItem], in_progress: char, depth: usize) {
+    let indent = "  ".repeat(depth);
+    for item in items {
+        let marker = match item.status {
+            Status::Todo => ' ',
+            Status::InProgress => in_progress,
+            Status::Completed => 'x',
+        };
+        out.push_str(&format!("{indent}- [{marker}] {}\n", item.todo));
+        for line in item.info.lines().filter(|line| !line.trim().is_empty()) {
+            out.push_str(&format!("{indent}  {}\n", line.trim()));
+        }
+        write_items(out, &item.children, in_progress, depth + 1);
+    }
+}
+
+/// Read the checklist items of a Markdown document, ignoring any other content.
+///
+/// Indented checklist items become subtasks, and indented text following an item its
+/// description.
+pub fn import(content: &str, in_progress: char) -> Vec<TodoItem> {
+    let mut items: Vec<TodoItem> = Vec::new();
+    // indentation and path of the items the next one could be nested in
+    let mut parents: Vec<(usize, ItemPath)> = Vec::new();
+    for line in content.lines() {
+        let indent = line.len() - line.trim_start().len();
+        if let Some((marker, name)) = parse_checkbox(line.trim_start()) {
+            let status = match marker {
+                'x' | 'X' => Status::Completed,
+                marker if marker == in_progress => Status::InProgress,
+                _ => Status::Todo,
+            };
+            let item = TodoItem::new(status, name, "");
+            while parents.last().is_some_and(|(depth, _)| *depth >= indent) {
+                parents.pop();
+            }
+            let path = match parents.last() {
+                Some((_, parent)) => {
+                    let Some(parent_item) = tree::get_mut(&mut items, parent) else {
+                        continue;
+                    };
+                    parent_item.children.push(item);
+                    let mut path = parent.clone();
+                    path.push(parent_item.children.len() - 1);
+                    path
+                }
+                None => {
+                    items.push(item);
+                    vec![items.len() - 1]
+                }
+            };
+            parents.push((indent, path));
+        } else if line.trim().is_empty() {
+            continue;
+        } else if let Some((_, path)) = parents.last().filter(|(depth, _)| indent > *depth) {
+            if let Some(item) = tree::get_mut(&mut items, path) {
+                if !item.info.is_empty() {
+                    item.info.push('\n');
+                }
+                item.info.push_str(line.trim());
+            }
+        } else {
+            // any other paragraph ends the checklist
+            parents.clear();
+        }
+    }
+    items
+}
+
+/// Split a `- [x] name` line into its marker and name.
+fn parse_checkbox(line: &str) -> Option<(char, &str)> {
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))?;
+    let rest = rest.strip_prefix('[')?;
+    let mut chars = rest.chars();
+    let marker = chars.next()?;
+    let name = chars.as_str().strip_prefix(']')?;
+    let name = name.trim();
+    (!name.is_empty()).then_some((marker, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(items: &[TodoItem]) -> Vec<(String, Status, String, usize)> {
+        let mut rows = Vec::new();
+        tree::for_each(items, &mut |item| {
+            rows.push((
+                item.todo.clone(),
+                item.status,
+                item.info.clone(),
+                item.children.len(),
+            ))
+        });
+        rows
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut parent = TodoItem::new(
+            Status::InProgress,
+            "release",
+            "tag the commit\nthen publish",
+        );
+        parent
+            .children
+            .push(TodoItem::new(Status::Completed, "changelog", ""));
+        parent
+            .children
+            .push(TodoItem::new(Status::Todo, "announce", "on the forum"));
+        let items = vec![parent, TodoItem::new(Status::Todo, "docs", "")];
+
+        let markdown = export(&items, IN_PROGRESS_MARKER);
+        assert_eq!(
+            markdown,
+            "- [~] release\n  tag the commit\n  then publish\n  - [x] changelog\n  - [ ] announce\n    on the forum\n- [ ] docs\n"
+        );
+        assert_eq!(
+            summary(&import(&markdown, IN_PROGRESS_MARKER)),
+            summary(&items)
+        );
+        // a different marker round trips as well
+        let markdown = export(&items, '-');
+        assert_eq!(summary(&import(&markdown, '-')), summary(&items));
+    }
+
+    #[test]
+    fn test_import_ignores_other_content() {
+        let content = "## TODO\n\nSome notes.\n\n* [X] done\n- [ ] open\n    details\nNot a detail\n- not a task\n";
+        let items = import(content, IN_PROGRESS_MARKER);
+        assert_eq!(
+            summary(&items),
+            vec![
+                ("done".to_string(), Status::Completed, String::new(), 0),
+                ("open".to_string(), Status::Todo, "details".to_string(), 0),
+            ]
+        );
+    }
+}
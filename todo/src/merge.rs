@@ -0,0 +1,237 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::base::{self, TodoItem};
+use crate::tree;
+use crate::undo::Snapshot;
+
+/// List holding a top-level task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Todo,
+    History,
+}
+
+/// Top-level tasks by id, with the list holding them.
+fn locate(snapshot: &Snapshot) -> HashMap<u64, (Side, &TodoItem)> {
+    let todo = snapshot
+        .todo
+        .iter()
+        .map(|item| (item.id, (Side::Todo, item)));
+    let history = snapshot
+        .history
+        .iter()
+        .map(|item| (item.id, (Side::History, item)));
+    todo.chain(history).collect()
+}
+
+/// Every task by id, subtasks included.
+fn index<'a>(items: &'a [TodoItem], found: &mut HashMap<u64, &'a TodoItem>) {
+    for item in items {
+        found.insert(item.id, item);
+        index(&item.children, found);
+    }
+}
+
+fn ids(items: &[TodoItem]) -> Vec<u64> {
+    items.iter().map(|item| item.id).collect()
+}
+
+/// Three-way merge of the lists edited in the app, `local`, with the ones found on disk,
+/// `remote`, both starting from `base`.
+///
+/// Top-level tasks are compared as a whole, subtasks included, along with the list holding them.
+/// A task changed on one side only takes the version of that side. A task changed differently on
+/// both sides keeps the version on disk, and is counted in the returned number of conflicts.
+/// Different tasks added on both sides with the same id are all kept, at any depth, the ones
+/// added in the app getting a new id, after `last_id` as in `base::assign_ids`.
+pub fn merge(
+    base: &Snapshot,
+    local: &Snapshot,
+    remote: &Snapshot,
+    last_id: &mut u64,
+) -> (Snapshot, usize) {
+    let mut remote = remote.clone();
+    // tasks added by hand in the files may not have an id yet
+    base::assign_ids(&mut remote.todo, &mut remote.history, last_id);
+    let (base_items, local_items, remote_items) = (locate(base), locate(local), locate(&remote));
+
+    let mut chosen: HashMap<u64, (Side, TodoItem)> = HashMap::new();
+    let mut renamed: Vec<(Side, TodoItem)> = Vec::new();
+    let mut conflicts = 0;
+    let all_ids: HashSet<u64> = local_items
+        .keys()
+        .chain(remote_items.keys())
+        .chain(base_items.keys())
+        .copied()
+        .collect();
+    for id in all_ids {
+        let base_item = base_items.get(&id);
+        let local_item = local_items.get(&id);
+        let remote_item = remote_items.get(&id);
+        let version = if local_item == base_item || local_item == remote_item {
+            remote_item
+        } else if remote_item == base_item {
+            local_item
+        } else {
+            if base_item.is_none() {
+                // both sides added a task with the next free id
+                renamed.extend(local_item.map(|(side, item)| (*side, (*item).clone())));
+            } else {
+                conflicts += 1;
+            }
+            remote_item
+        };
+        if let Some((side, item)) = version {
+            chosen.insert(id, (*side, (*item).clone()));
+        }
+    }
+
+    let mut merged = Snapshot {
+        todo: Vec::new(),
+        history: Vec::new(),
+    };
+    let lists = [
+        (Side::Todo, &base.todo, &local.todo, &remote.todo),
+        (
+            Side::History,
+            &base.history,
+            &local.history,
+            &remote.history,
+        ),
+    ];
+    for (side, base_list, local_list, remote_list) in lists {
+        // keep the order changed by hand in the app, else the one on disk
+        let order: Vec<u64> = if ids(local_list) == ids(base_list) {
+            ids(remote_list)
+                .into_iter()
+                .chain(ids(local_list))
+                .collect()
+        } else {
+            ids(local_list)
+                .into_iter()
+                .chain(ids(remote_list))
+                .collect()
+        };
+        let merged_list = match side {
+            Side::Todo => &mut merged.todo,
+            Side::History => &mut merged.history,
+        };
+        for id in order {
+            if chosen
+                .get(&id)
+                .is_some_and(|(chosen_side, _)| *chosen_side == side)
+            {
+                merged_list.extend(chosen.remove(&id).map(|(_, item)| item));
+            }
+        }
+        for (_, mut item) in renamed.extract_if(.., |(renamed_side, _)| *renamed_side == side) {
+            item.id = 0;
+            merged_list.push(item);
+        }
+    }
+
+    // subtasks added in the app may have taken the id of a task added on disk
+    let mut base_ids = HashSet::new();
+    for items in [&base.todo, &base.history] {
+        tree::for_each(items, &mut |item| {
+            base_ids.insert(item.id);
+        });
+    }
+    let mut on_disk = HashMap::new();
+    index(&remote.todo, &mut on_disk);
+    index(&remote.history, &mut on_disk);
+    let mut seen = HashSet::new();
+    for items in [&mut merged.todo, &mut merged.history] {
+        tree::for_each_mut(items, &mut |item| {
+            let taken_on_disk = !base_ids.contains(&item.id)
+                && on_disk
+                    .get(&item.id)
+                    .is_some_and(|disk_item| *disk_item != item);
+            if taken_on_disk || seen.contains(&item.id) {
+                item.id = 0;
+            } else {
+                seen.insert(item.id);
+            }
+        });
+    }
+    base::assign_ids(&mut merged.todo, &mut merged.history, last_id);
+    (merged, conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::Status;
+
+    fn item(id: u64, name: &str) -> TodoItem {
+        let mut item = TodoItem::new(Status::Todo, name, "");
+        item.id = id;
+        item
+    }
+
+    fn names(items: &[TodoItem]) -> Vec<&str> {
+        items.iter().map(|item| item.todo.as_str()).collect()
+    }
+
+    #[test]
+    fn test_merge_independent_changes() {
+        let base = Snapshot {
+            todo: vec![item(1, "a"), item(2, "b"), item(3, "c")],
+            history: vec![],
+        };
+        let mut local = base.clone();
+        local.todo[0].set_status(Status::InProgress);
+        local.todo.push(item(4, "local"));
+        let mut remote = base.clone();
+        // flushed from the CLI
+        remote.todo[1].set_status(Status::Completed);
+        let flushed = remote.todo.remove(1);
+        remote.history.push(flushed);
+        remote.todo.push(item(4, "remote"));
+
+        let (merged, conflicts) = merge(&base, &local, &remote, &mut 0);
+        assert_eq!(conflicts, 0);
+        assert_eq!(names(&merged.todo), ["a", "c", "remote", "local"]);
+        assert_eq!(names(&merged.history), ["b"]);
+        assert_eq!(merged.todo[0].status, Status::InProgress);
+        assert_eq!(merged.todo[3].id, 5);
+    }
+
+    #[test]
+    fn test_merge_conflict_keeps_disk() {
+        let base = Snapshot {
+            todo: vec![item(1, "a"), item(2, "b")],
+            history: vec![],
+        };
+        let mut local = base.clone();
+        local.todo[0].todo = "local a".to_string();
+        local.todo.remove(1);
+        let mut remote = base.clone();
+        remote.todo[0].todo = "remote a".to_string();
+
+        let (merged, conflicts) = merge(&base, &local, &remote, &mut 0);
+        assert_eq!(conflicts, 1);
+        // the deletion in the app does not conflict with anything
+        assert_eq!(names(&merged.todo), ["remote a"]);
+    }
+
+    #[test]
+    fn test_merge_renames_nested_additions() {
+        let base = Snapshot {
+            todo: vec![item(1, "a")],
+            history: vec![],
+        };
+        let mut local = base.clone();
+        local.todo[0].children.push(item(2, "local child"));
+        let mut remote = base.clone();
+        remote.todo.push(item(2, "remote"));
+
+        let (merged, conflicts) = merge(&base, &local, &remote, &mut 0);
+        assert_eq!(conflicts, 0);
+        assert_eq!(names(&merged.todo), ["a", "remote"]);
+        assert_eq!(names(&merged.todo[0].children), ["local child"]);
+        // the task added on disk keeps its id
+        assert_eq!(merged.todo[1].id, 2);
+        assert_eq!(merged.todo[0].children[0].id, 3);
+    }
+}
@@ -0,0 +1,199 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::config::theme;
+use crate::form::StringField;
+use crate::popup;
+
+/// What a dialog asks for.
+enum Kind {
+    /// A yes or no question.
+    Confirm,
+    Info,
+    Error,
+    /// A line of text.
+    Prompt(StringField),
+}
+
+/// Dialog shown over the app, taking the key presses until it is closed.
+///
+/// Confirmations and prompts carry the `action` to run once accepted.
+pub struct Modal<A> {
+    kind: Kind,
+    title: String,
+    message: String,
+    action: Option<A>,
+}
+
+/// Result of a key press in a dialog.
+pub enum Outcome<A> {
+    /// The dialog stays open.
+    Open,
+    /// The dialog was dismissed without accepting it.
+    Closed,
+    /// The dialog was accepted, with the text typed in a prompt.
+    Accepted(A, String),
+}
+
+impl<A> Modal<A> {
+    pub fn confirm(message: impl Into<String>, action: A) -> Self {
+        Self {
+            kind: Kind::Confirm,
+            title: "Confirm".to_string(),
+            message: message.into(),
+            action: Some(action),
+        }
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self {
+            kind: Kind::Info,
+            title: "Info".to_string(),
+            message: message.into(),
+            action: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            kind: Kind::Error,
+            title: "Error".to_string(),
+            message: message.into(),
+            action: None,
+        }
+    }
+
+    /// Ask for a line of text, starting from `value`.
+    pub fn prompt(title: &str, label: &'static str, value: &str, action: A) -> Self {
+        let mut field = StringField::new(label);
+        field.set_value(value);
+        Self {
+            kind: Kind::Prompt(field),
+            title: title.to_string(),
+            message: String::new(),
+            action: Some(action),
+        }
+    }
+
+    pub fn on_key(&mut self, key: KeyEvent) -> Outcome<A> {
+        if key.kind != KeyEventKind::Press {
+            return Outcome::Open;
+        }
+        let accepted = match (&mut self.kind, key.code) {
+            (Kind::Confirm, KeyCode::Char('y') | KeyCode::Enter) => true,
+            (Kind::Confirm, KeyCode::Char('n' | 'q') | KeyCode::Esc) => false,
+            (
+                Kind::Info | Kind::Error,
+                KeyCode::Char('q' | ' ') | KeyCode::Enter | KeyCode::Esc,
+            ) => false,
+            (Kind::Prompt(_), KeyCode::Enter) => true,
+            (Kind::Prompt(_), KeyCode::Esc) => false,
+            (Kind::Prompt(field), _) => {
+                field.on_key_press(key);
+                return Outcome::Open;
+            }
+            _ => return Outcome::Open,
+        };
+        match self.action.take() {
+            Some(action) if accepted => Outcome::Accepted(action, self.value().to_string()),
+            _ => Outcome::Closed,
+        }
+    }
+
+    pub fn on_paste(&mut self, text: &str) {
+        if let Kind::Prompt(field) = &mut self.kind {
+            field.on_paste(text);
+        }
+    }
+
+    /// Text typed in a prompt, empty for the other dialogs.
+    pub fn value(&self) -> &str {
+        match &self.kind {
+            Kind::Prompt(field) => field.value(),
+            _ => "",
+        }
+    }
+
+    /// Render the dialog centered in `area`, returning the cursor position of a prompt.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) -> Option<Position> {
+        let popup_area = popup::popup_area(area, 50, 30);
+        let mut block = Block::bordered()
+            .title(Line::raw(self.title.as_str()).centered())
+            .style(theme().header_style());
+        if let Kind::Error = self.kind {
+            block = block.border_style(Style::new().fg(theme().error_fg));
+        }
+        let content = block.inner(popup_area);
+        Clear.render(popup_area, buf);
+        block.render(popup_area, buf);
+
+        let [message_area, input_area, help_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(content);
+        Paragraph::new(self.message.as_str())
+            .centered()
+            .wrap(Wrap { trim: true })
+            .render(message_area, buf);
+        let help = match self.kind {
+            Kind::Confirm => "y yes, n no",
+            Kind::Info | Kind::Error => "Enter close",
+            Kind::Prompt(_) => "Enter confirm, Esc cancel",
+        };
+        Paragraph::new(Line::from(help).italic())
+            .centered()
+            .render(help_area, buf);
+        let Kind::Prompt(field) = &self.kind else {
+            return None;
+        };
+        field.render(input_area, buf);
+        Some(field.cursor_position(input_area))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn press(modal: &mut Modal<u8>, code: KeyCode) -> Outcome<u8> {
+        modal.on_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_modal_outcomes() {
+        let mut confirm = Modal::confirm("Delete?", 1);
+        assert!(matches!(
+            press(&mut confirm, KeyCode::Char('x')),
+            Outcome::Open
+        ));
+        assert!(matches!(
+            press(&mut confirm, KeyCode::Char('y')),
+            Outcome::Accepted(1, _)
+        ));
+        let mut confirm = Modal::confirm("Delete?", 1);
+        assert!(matches!(press(&mut confirm, KeyCode::Esc), Outcome::Closed));
+
+        let mut error: Modal<u8> = Modal::error("Could not save");
+        assert!(matches!(
+            press(&mut error, KeyCode::Char('y')),
+            Outcome::Open
+        ));
+        assert!(matches!(press(&mut error, KeyCode::Enter), Outcome::Closed));
+
+        let mut prompt = Modal::prompt("Rename", "Name", "old", 2);
+        press(&mut prompt, KeyCode::Char('s'));
+        match press(&mut prompt, KeyCode::Enter) {
+            Outcome::Accepted(2, value) => assert_eq!(value, "olds"),
+            _ => panic!("the prompt was not accepted"),
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Days searched for the next match of a cron rule, enough for any valid one.
+const CRON_SEARCH_DAYS: u64 = 366 * 8;
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// Unit of a fixed interval between occurrences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// How often a task comes back once completed.
+///
+/// Written as `daily`, `weekly`, `monthly`, `yearly`, `every 2 weeks`, or a cron rule such as
+/// `0 9 * * mon` whose minute and hour fields are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Recurrence {
+    Every(u32, Period),
+    Cron(CronDays),
+}
+
+/// Day of month, month and day of week fields of a cron rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronDays {
+    rule: String,
+    days: Vec<u32>,
+    months: Vec<u32>,
+    /// From 0 for Sunday to 6 for Saturday.
+    weekdays: Vec<u32>,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl Recurrence {
+    /// Due date of the occurrence following one due on `due`, in the future of `today`.
+    ///
+    /// Tasks without a due date repeat from `today`.
+    pub fn next_due(&self, due: Option<NaiveDate>, today: NaiveDate) -> Option<NaiveDate> {
+        let start = due.unwrap_or(today);
+        match self {
+            Self::Every(count, period) => {
+                let mut next = advance(start, *count, *period)?;
+                // skip the occurrences missed while the task was overdue
+                while next <= today {
+                    next = advance(next, *count, *period)?;
+                }
+                Some(next)
+            }
+            Self::Cron(cron) => cron.next_after(start.max(today)),
+        }
+    }
+}
+
+fn advance(date: NaiveDate, count: u32, period: Period) -> Option<NaiveDate> {
+    match period {
+        Period::Day => date.checked_add_days(Days::new(count.into())),
+        Period::Week => date.checked_add_days(Days::new(7 * u64::from(count))),
+        Period::Month => date.checked_add_months(Months::new(count)),
+        Period::Year => date.checked_add_months(Months::new(count.checked_mul(12)?)),
+    }
+}
+
+impl CronDays {
+    fn matches(&self, date: NaiveDate) -> bool {
+        let day = self.days.contains(&date.day());
+        let weekday = self
+            .weekdays
+            .contains(&date.weekday().num_days_from_sunday());
+        // as in cron, a date matches either restricted day field
+        let day_matches = match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday,
+            (false, true) => day,
+            (false, false) => day || weekday,
+        };
+        day_matches && self.months.contains(&date.month())
+    }
+
+    fn next_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+        (1..=CRON_SEARCH_DAYS)
+            .filter_map(|offset| date.checked_add_days(Days::new(offset)))
+            .find(|date| self.matches(*date))
+    }
+}
+
+impl FromStr for CronDays {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let [days, months, weekdays] = match fields[..] {
+            [_, _, days, months, weekdays] | [days, months, weekdays] => [days, months, weekdays],
+            _ => return Err(format!("invalid cron rule '{s}', expected 3 or 5 fields")),
+        };
+        let mut weekday_values = parse_field(weekdays, 0, 7, &WEEKDAY_NAMES, 0)?;
+        // 7 is Sunday as well
+        for weekday in &mut weekday_values {
+            *weekday %= 7;
+        }
+        let cron = Self {
+            rule: s.split_whitespace().collect::<Vec<_>>().join(" "),
+            days: parse_field(days, 1, 31, &[], 1)?,
+            months: parse_field(months, 1, 12, &MONTH_NAMES, 1)?,
+            weekdays: weekday_values,
+            any_day: days == "*",
+            any_weekday: weekdays == "*",
+        };
+        if cron.next_after(NaiveDate::MIN).is_none() {
+            return Err(format!("cron rule '{s}' never matches"));
+        }
+        Ok(cron)
+    }
+}
+
+/// Values of a cron field made of `*`, values, `a-b` ranges and `/step`, separated by commas.
+///
+/// `names` are accepted for the values, starting from `first`.
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    first: u32,
+) -> Result<Vec<u32>, String> {
+    let value = |s: &str| -> Result<u32, String> {
+        let lower = s.to_lowercase();
+        let value = match names.iter().position(|name| *name == lower) {
+            Some(i) => i as u32 + first,
+            None => s.parse().map_err(|_| format!("invalid cron value '{s}'"))?,
+        };
+        if (min..=max).contains(&value) {
+            Ok(value)
+        } else {
+            Err(format!("cron value {value} is not between {min} and {max}"))
+        }
+    };
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid cron step '{step}'"))?;
+                (range, step.max(1))
+            }
+            None => (part, 1),
+        };
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (value(start)?, value(end)?),
+            None => (value(range)?, value(range)?),
+        };
+        values.extend((start..=end).step_by(step as usize));
+    }
+    Ok(values)
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rule = s.trim().to_lowercase();
+        match rule.as_str() {
+            "daily" => return Ok(Self::Every(1, Period::Day)),
+            "weekly" => return Ok(Self::Every(1, Period::Week)),
+            "monthly" => return Ok(Self::Every(1, Period::Month)),
+            "yearly" => return Ok(Self::Every(1, Period::Year)),
+            _ => {}
+        }
+        if let Some(interval) = rule.strip_prefix("every ") {
+            let (count, unit) = match interval.split_once(' ') {
+                Some((count, unit)) => (
+                    count
+                        .parse()
+                        .map_err(|_| format!("invalid interval '{count}'"))?,
+                    unit,
+                ),
+                None => (1, interval),
+            };
+            let period = match unit.trim_end_matches('s') {
+                "day" => Period::Day,
+                "week" => Period::Week,
+                "month" => Period::Month,
+                "year" => Period::Year,
+                other => return Err(format!("unknown period '{other}'")),
+            };
+            if count == 0 {
+                return Err("the interval must be at least 1".to_string());
+            }
+            if advance(NaiveDate::default(), count, period).is_none() {
+                return Err(format!("the interval '{interval}' is too long"));
+            }
+            return Ok(Self::Every(count, period));
+        }
+        if rule.split_whitespace().count() >= 3 {
+            return s.parse().map(Self::Cron);
+        }
+        Err(format!(
+            "unknown recurrence '{s}', expected daily, weekly, monthly, yearly, every N days or a cron rule"
+        ))
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Every(1, Period::Day) => f.write_str("daily"),
+            Self::Every(1, Period::Week) => f.write_str("weekly"),
+            Self::Every(1, Period::Month) => f.write_str("monthly"),
+            Self::Every(1, Period::Year) => f.write_str("yearly"),
+            Self::Every(count, period) => {
+                let unit = match period {
+                    Period::Day => "days",
+                    Period::Week => "weeks",
+                    Period::Month => "months",
+                    Period::Year => "years",
+                };
+                write!(f, "every {count} {unit}")
+            }
+            Self::Cron(cron) => f.write_str(&cron.rule),
+        }
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(value: Recurrence) -> Self {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for rule in ["daily", "weekly", "monthly", "every 2 weeks", "0 9 * * mon"] {
+            let recurrence: Recurrence = rule.parse().unwrap();
+            assert_eq!(recurrence.to_string(), rule);
+        }
+        assert_eq!(
+            "every 3 days".parse(),
+            Ok(Recurrence::Every(3, Period::Day))
+        );
+        assert_eq!(
+            "every month".parse(),
+            Ok(Recurrence::Every(1, Period::Month))
+        );
+        assert!("every 0 days".parse::<Recurrence>().is_err());
+        assert!("every 400000000 years".parse::<Recurrence>().is_err());
+        assert!("every 4000000000 days".parse::<Recurrence>().is_err());
+        assert!("sometimes".parse::<Recurrence>().is_err());
+        assert!("* * 9".parse::<Recurrence>().is_err());
+        assert!("31 2 *".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn test_next_due() {
+        let today = date(2026, 1, 14); // a Wednesday
+        let weekly: Recurrence = "weekly".parse().unwrap();
+        assert_eq!(weekly.next_due(Some(today), today), Some(date(2026, 1, 21)));
+        // missed occurrences are skipped
+        assert_eq!(
+            weekly.next_due(Some(date(2025, 12, 31)), today),
+            Some(date(2026, 1, 21))
+        );
+        assert_eq!(weekly.next_due(None, today), Some(date(2026, 1, 21)));
+        // intervals past the last supported date have no next occurrence
+        assert_eq!(advance(today, 400_000_000, Period::Year), None);
+
+        let monthly: Recurrence = "monthly".parse().unwrap();
+        assert_eq!(
+            monthly.next_due(Some(date(2026, 1, 31)), date(2026, 1, 31)),
+            Some(date(2026, 2, 28))
+        );
+
+        let mondays: Recurrence = "0 9 * * mon".parse().unwrap();
+        assert_eq!(mondays.next_due(None, today), Some(date(2026, 1, 19)));
+        let first_of_quarter: Recurrence = "1 */3 *".parse().unwrap();
+        assert_eq!(
+            first_of_quarter.next_due(None, today),
+            Some(date(2026, 4, 1))
+        );
+    }
+}
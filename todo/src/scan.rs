@@ -0,0 +1,257 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::base::{Status, TodoItem};
+
+/// Topic holding the tasks found in the source code.
+pub const CODE_TOPIC: &str = "code";
+
+const MARKERS: [&str; 2] = ["TODO:", "FIXME:"];
+/// Text a marker must follow to be in a comment rather than in code or a string.
+const COMMENT_LEADERS: [&str; 8] = ["//", "//!", "#", "/*", "*", "--", "<!--", ";"];
+/// Folders never worth scanning, on top of the hidden ones.
+const SKIPPED_FOLDERS: [&str; 3] = ["target", "node_modules", "vendor"];
+const MAX_FILE_SIZE: u64 = 1 << 20;
+
+/// A `TODO:` or `FIXME:` comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeComment {
+    /// Path relative to the scanned folder, with `/` separators.
+    pub file: String,
+    pub line: usize,
+    /// `TODO` or `FIXME`.
+    pub kind: String,
+    pub text: String,
+}
+
+impl CodeComment {
+    fn location(&self) -> String {
+        format!("{}:{}", self.file, self.line)
+    }
+}
+
+/// Find the comments of every text file under `root`, skipping hidden and build folders, and
+/// links to folders.
+pub fn scan_tree(root: &Path) -> Vec<CodeComment> {
+    let mut comments = Vec::new();
+    scan_folder(root, root, &mut comments);
+    comments
+}
+
+fn scan_folder(root: &Path, folder: &Path, comments: &mut Vec<CodeComment>) {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return;
+    };
+    // the type of the entry itself, links not being followed
+    let mut entries: Vec<(PathBuf, fs::FileType)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            Some((entry.path(), entry.file_type().ok()?))
+        })
+        .collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (path, file_type) in entries {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        if file_type.is_dir() {
+            if !SKIPPED_FOLDERS.contains(&name) {
+                scan_folder(root, &path, comments);
+            }
+            continue;
+        }
+        // links to folders can loop back to a parent, or lead out of the project
+        if file_type.is_symlink() && path.is_dir() {
+            continue;
+        }
+        if path
+            .metadata()
+            .map_or(true, |meta| meta.len() > MAX_FILE_SIZE)
+        {
+            continue;
+        }
+        // binary files are not valid UTF-8
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let file = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        for (i, line) in content.lines().enumerate() {
+            if let Some((kind, text)) = parse_comment(line) {
+                comments.push(CodeComment {
+                    file: file.clone(),
+                    line: i + 1,
+                    kind: kind.to_string(),
+                    text,
+                });
+            }
+        }
+    }
+}
+
+/// Kind and text of the marker comment on the line, if any.
+fn parse_comment(line: &str) -> Option<(&'static str, String)> {
+    MARKERS.iter().find_map(|marker| {
+        let start = line.find(marker)?;
+        let before = line[..start].trim_end();
+        if !COMMENT_LEADERS
+            .iter()
+            .any(|leader| before.ends_with(leader))
+        {
+            return None;
+        }
+        let text = line[start + marker.len()..]
+            .trim()
+            .trim_end_matches("*/")
+            .trim_end_matches("-->")
+            .trim();
+        let kind = marker.trim_end_matches(':');
+        let text = if text.is_empty() { kind } else { text };
+        Some((kind, text.to_string()))
+    })
+}
+
+/// File of a task created from a comment, whose description is its `file:line` location.
+fn location_file(item: &TodoItem) -> Option<&str> {
+    let (file, line) = item.info.rsplit_once(':')?;
+    line.parse::<usize>().ok()?;
+    Some(file)
+}
+
+/// Update the tasks created from comments with a new scan.
+///
+/// A comment matches the task with the same text in the same file, so that moving it around
+/// only updates the line. Tasks whose comment disappeared are completed, and reopened if it comes
+/// back. Tasks added by hand, without a location, are left alone.
+pub fn sync(items: &mut Vec<TodoItem>, comments: &[CodeComment]) {
+    let mut found = vec![false; items.len()];
+    for comment in comments {
+        let matching = items.iter().enumerate().position(|(i, item)| {
+            !found[i] && item.todo == comment.text && location_file(item) == Some(&comment.file)
+        });
+        match matching {
+            Some(i) => {
+                found[i] = true;
+                let item = &mut items[i];
+                if item.status == Status::Completed {
+                    item.set_status(Status::Todo);
+                }
+                let location = comment.location();
+                if item.info != location {
+                    item.info = location;
+                    item.updated_at = chrono::Utc::now();
+                }
+            }
+            None => {
+                let mut item = TodoItem::new(Status::Todo, &comment.text, &comment.location());
+                item.tags = vec![comment.kind.to_lowercase()];
+                items.push(item);
+                found.push(true);
+            }
+        }
+    }
+    for (item, found) in items.iter_mut().zip(found) {
+        if !found && item.status != Status::Completed && location_file(item).is_some() {
+            item.set_status(Status::Completed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TempFolder;
+
+    fn comment(file: &str, line: usize, text: &str) -> CodeComment {
+        CodeComment {
+            file: file.to_string(),
+            line,
+            kind: "TODO".to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_comment() {
+        assert_eq!(
+            parse_comment("    // TODO: handle errors"),
+            Some(("TODO", "handle errors".to_string()))
+        );
+        assert_eq!(
+            parse_comment("x = 1  # FIXME: off by one"),
+            Some(("FIXME", "off by one".to_string()))
+        );
+        assert_eq!(
+            parse_comment("/* TODO: remove */"),
+            Some(("TODO", "remove".to_string()))
+        );
+        assert_eq!(
+            parse_comment("// TODO:"),
+            Some(("TODO", "TODO".to_string()))
+        );
+        // markers in code or strings are not comments
+        assert_eq!(parse_comment(r#"let marker = "TODO: text";"#), None);
+        assert_eq!(parse_comment("TODO: not a comment"), None);
+    }
+
+    #[test]
+    fn test_sync() {
+        let mut items = vec![TodoItem::new(Status::Todo, "by hand", "")];
+        sync(
+            &mut items,
+            &[
+                comment("src/a.rs", 3, "first"),
+                comment("src/b.rs", 7, "second"),
+            ],
+        );
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1].info, "src/a.rs:3");
+        assert_eq!(items[2].tags, vec!["todo".to_string()]);
+
+        // moved comments are not duplicated, and removed ones are completed
+        sync(&mut items, &[comment("src/a.rs", 10, "first")]);
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1].info, "src/a.rs:10");
+        assert_eq!(items[1].status, Status::Todo);
+        assert_eq!(items[2].status, Status::Completed);
+        assert_eq!(items[0].status, Status::Todo);
+
+        // a comment coming back reopens its task
+        sync(
+            &mut items,
+            &[
+                comment("src/a.rs", 10, "first"),
+                comment("src/b.rs", 8, "second"),
+            ],
+        );
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[2].status, Status::Todo);
+        assert_eq!(items[2].info, "src/b.rs:8");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_skips_linked_folders() {
+        let folder = TempFolder::new("scan");
+        let src = folder.path().join("src");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("main.rs"), "// TODO: only once\n").unwrap();
+        std::os::unix::fs::symlink("..", src.join("up")).unwrap();
+        std::os::unix::fs::symlink("main.rs", src.join("linked.rs")).unwrap();
+
+        let comments = scan_tree(folder.path());
+        let files: Vec<&str> = comments
+            .iter()
+            .map(|comment| comment.file.as_str())
+            .collect();
+        // linked files are still read
+        assert_eq!(files, ["src/linked.rs", "src/main.rs"]);
+    }
+}
@@ -0,0 +1,148 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::base::TodoItem;
+
+/// Field the lists can be sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Status,
+    Created,
+    Priority,
+    Due,
+}
+
+/// Order applied when sorting a list, cycled through with the sort action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortMode {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl Default for SortMode {
+    fn default() -> Self {
+        Self {
+            key: SortKey::Name,
+            descending: false,
+        }
+    }
+}
+
+impl SortMode {
+    /// Each key is used ascending then descending, before moving on to the next key.
+    pub fn next(self) -> Self {
+        if !self.descending {
+            return Self {
+                descending: true,
+                ..self
+            };
+        }
+        let key = match self.key {
+            SortKey::Name => SortKey::Status,
+            SortKey::Status => SortKey::Created,
+            SortKey::Created => SortKey::Priority,
+            SortKey::Priority => SortKey::Due,
+            SortKey::Due => SortKey::Name,
+        };
+        Self {
+            key,
+            descending: false,
+        }
+    }
+
+    /// Stable sort of the items and of their children.
+    ///
+    /// Items without a priority or a due date always come last.
+    pub fn sort(&self, items: &mut [TodoItem]) {
+        items.sort_by(|a, b| self.compare(a, b));
+        for item in items {
+            self.sort(&mut item.children);
+        }
+    }
+
+    fn compare(&self, a: &TodoItem, b: &TodoItem) -> Ordering {
+        let ordering = match self.key {
+            SortKey::Name => a.todo.to_lowercase().cmp(&b.todo.to_lowercase()),
+            SortKey::Status => a.status.cmp(&b.status),
+            SortKey::Created => a.created_at.cmp(&b.created_at),
+            SortKey::Priority => return compare_optional(a.priority, b.priority, self.descending),
+            SortKey::Due => return compare_optional(a.due, b.due, self.descending),
+        };
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+fn compare_optional<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if descending => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+impl fmt::Display for SortMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = match self.key {
+            SortKey::Name => "name",
+            SortKey::Status => "status",
+            SortKey::Created => "created",
+            SortKey::Priority => "priority",
+            SortKey::Due => "due",
+        };
+        let arrow = if self.descending { "↓" } else { "↑" };
+        write!(f, "{key} {arrow}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{Priority, Status};
+
+    #[test]
+    fn test_sort_modes() {
+        let mut items: Vec<TodoItem> = [
+            ("b", Some(Priority::P2)),
+            ("c", None),
+            ("a", Some(Priority::P0)),
+        ]
+        .into_iter()
+        .map(|(name, priority)| {
+            let mut item = TodoItem::new(Status::Todo, name, "");
+            item.priority = priority;
+            item
+        })
+        .collect();
+        let names = |items: &[TodoItem]| {
+            items
+                .iter()
+                .map(|item| item.todo.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut mode = SortMode::default();
+        mode.sort(&mut items);
+        assert_eq!(names(&items), ["a", "b", "c"]);
+        mode = mode.next();
+        mode.sort(&mut items);
+        assert_eq!(names(&items), ["c", "b", "a"]);
+
+        let priority = SortMode {
+            key: SortKey::Priority,
+            descending: false,
+        };
+        priority.sort(&mut items);
+        assert_eq!(names(&items), ["a", "b", "c"]);
+        // items without priority stay last
+        priority.next().sort(&mut items);
+        assert_eq!(names(&items), ["b", "a", "c"]);
+        assert_eq!(priority.next().next().key, SortKey::Due);
+    }
+}
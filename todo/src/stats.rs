@@ -0,0 +1,194 @@
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, TimeDelta, Utc};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{BarChart, Block, Borders, Paragraph, Sparkline, Widget},
+};
+
+use crate::base::{format_age, Status, TodoItem};
+use crate::config::theme;
+use crate::tree;
+
+/// Number of days shown in the daily completions.
+const DAYS: usize = 30;
+/// Number of weeks shown in the weekly completions.
+const WEEKS: usize = 8;
+/// Number of open tasks listed as the oldest ones.
+const OLDEST: usize = 5;
+
+/// Throughput of a topic, computed from the timestamps of its tasks.
+pub struct Stats {
+    pub todo: usize,
+    pub in_progress: usize,
+    pub completed: usize,
+    /// Completions of each of the last days, the oldest first.
+    pub per_day: Vec<u64>,
+    /// Monday and completions of each of the last weeks, the oldest first.
+    pub per_week: Vec<(NaiveDate, u64)>,
+    /// Average time from creation to completion.
+    pub average_completion: Option<TimeDelta>,
+    /// Name and creation date of the open tasks created first.
+    pub oldest_open: Vec<(String, DateTime<Utc>)>,
+    pub now: DateTime<Utc>,
+}
+
+impl Stats {
+    /// Compute the statistics of both lists, subtasks included.
+    pub fn new(todo: &[TodoItem], history: &[TodoItem], now: DateTime<Utc>) -> Self {
+        let today = now.with_timezone(&Local).date_naive();
+        let this_week = today - Days::new(u64::from(today.weekday().num_days_from_monday()));
+        let mut stats = Self {
+            todo: 0,
+            in_progress: 0,
+            completed: 0,
+            per_day: vec![0; DAYS],
+            per_week: (0..WEEKS)
+                .rev()
+                .map(|i| (this_week - Days::new(7 * i as u64), 0))
+                .collect(),
+            average_completion: None,
+            oldest_open: Vec::new(),
+            now,
+        };
+        let mut total_completion = TimeDelta::zero();
+        let mut timed = 0;
+        for items in [todo, history] {
+            tree::for_each(items, &mut |item| {
+                match item.status {
+                    Status::Todo => stats.todo += 1,
+                    Status::InProgress => stats.in_progress += 1,
+                    Status::Completed => stats.completed += 1,
+                }
+                if item.status != Status::Completed {
+                    stats.oldest_open.push((item.todo.clone(), item.created_at));
+                }
+                let Some(completed_at) = item.completed_at else {
+                    return;
+                };
+                total_completion += completed_at - item.created_at;
+                timed += 1;
+                let day = completed_at.with_timezone(&Local).date_naive();
+                let days_ago = (today - day).num_days();
+                if (0..DAYS as i64).contains(&days_ago) {
+                    stats.per_day[DAYS - 1 - days_ago as usize] += 1;
+                }
+                if let Some((_, count)) = stats
+                    .per_week
+                    .iter_mut()
+                    .rev()
+                    .find(|(monday, _)| *monday <= day)
+                    .filter(|(monday, _)| (day - *monday).num_days() < 7)
+                {
+                    *count += 1;
+                }
+            });
+        }
+        if timed > 0 {
+            stats.average_completion = Some(total_completion / timed);
+        }
+        stats.oldest_open.sort_by_key(|(_, created_at)| *created_at);
+        stats.oldest_open.truncate(OLDEST);
+        stats
+    }
+}
+
+impl Widget for &Stats {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [summary_area, days_area, weeks_area] = Layout::vertical([
+            Constraint::Length(7),
+            Constraint::Length(6),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [counts_area, oldest_area] =
+            Layout::horizontal([Constraint::Fill(1); 2]).areas(summary_area);
+
+        let average = match self.average_completion {
+            Some(average) => format_age(self.now - average, self.now),
+            None => "-".to_string(),
+        };
+        Paragraph::new(vec![
+            Line::raw(format!("To do: {}", self.todo)),
+            Line::raw(format!("In progress: {}", self.in_progress)).fg(theme().in_progress_fg),
+            Line::raw(format!("Completed: {}", self.completed)).fg(theme().completed_fg),
+            Line::raw(""),
+            Line::raw(format!("Time to complete: {average}")),
+        ])
+        .block(section("Tasks"))
+        .render(counts_area, buf);
+
+        let oldest: Vec<Line> = self
+            .oldest_open
+            .iter()
+            .map(|(name, created_at)| {
+                Line::raw(format!("{name}, {} old", format_age(*created_at, self.now)))
+            })
+            .collect();
+        Paragraph::new(oldest)
+            .block(section("Oldest open tasks"))
+            .render(oldest_area, buf);
+
+        Sparkline::default()
+            .block(section(&format!("Completed per day, last {DAYS} days")))
+            .data(&self.per_day)
+            .style(theme().completed_fg)
+            .render(days_area, buf);
+
+        let labels: Vec<String> = self
+            .per_week
+            .iter()
+            .map(|(monday, _)| monday.format("%m-%d").to_string())
+            .collect();
+        let bars: Vec<(&str, u64)> = labels
+            .iter()
+            .zip(&self.per_week)
+            .map(|(label, (_, count))| (label.as_str(), *count))
+            .collect();
+        BarChart::default()
+            .block(section("Completed per week"))
+            .data(bars.as_slice())
+            .bar_width(5)
+            .bar_gap(2)
+            .bar_style(theme().completed_fg)
+            .value_style(theme().header_style())
+            .render(weeks_area, buf);
+    }
+}
+
+fn section(title: &str) -> Block<'_> {
+    Block::new()
+        .title(Line::raw(title).centered())
+        .borders(Borders::TOP)
+        .border_style(theme().header_style())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let now = Utc::now();
+        let mut done = TodoItem::new(Status::Todo, "done", "");
+        done.created_at = now - TimeDelta::days(3);
+        done.set_status(Status::Completed);
+        let mut old = TodoItem::new(Status::InProgress, "old", "");
+        old.created_at = now - TimeDelta::days(10);
+        old.children.push(TodoItem::new(Status::Todo, "child", ""));
+
+        let stats = Stats::new(&[old], &[done], now);
+        assert_eq!((stats.todo, stats.in_progress, stats.completed), (1, 1, 1));
+        assert_eq!(stats.per_day.iter().sum::<u64>(), 1);
+        assert_eq!(stats.per_day[DAYS - 1], 1);
+        assert_eq!(stats.per_week.last().map(|(_, count)| *count), Some(1));
+        assert_eq!(stats.average_completion.map(|d| d.num_days()), Some(3));
+        let oldest: Vec<&str> = stats
+            .oldest_open
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(oldest, ["old", "child"]);
+    }
+}
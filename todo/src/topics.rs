@@ -0,0 +1,61 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{
+        Block, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget,
+    },
+};
+
+/// Panel listing the topics found in the cache folder.
+pub struct TopicPanel {
+    pub topics: Vec<String>,
+    pub state: ListState,
+}
+
+impl TopicPanel {
+    pub fn new(topics: Vec<String>, current: &str) -> Self {
+        let mut state = ListState::default();
+        state.select(topics.iter().position(|topic| topic == current));
+        Self { topics, state }
+    }
+
+    pub fn selected(&self) -> Option<&String> {
+        self.state.selected().and_then(|i| self.topics.get(i))
+    }
+
+    pub fn render(
+        &mut self,
+        area: Rect,
+        buf: &mut Buffer,
+        current: &str,
+        help: &str,
+        style: Style,
+    ) {
+        let [list_area, help_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+
+        let items: Vec<ListItem> = self
+            .topics
+            .iter()
+            .map(|topic| {
+                if topic == current {
+                    ListItem::new(format!("{topic} *"))
+                } else {
+                    ListItem::new(topic.as_str())
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::new())
+            .highlight_style(style)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut self.state);
+
+        Paragraph::new(Line::from(help).italic())
+            .centered()
+            .render(help_area, buf);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::base::TodoItem;
+
+/// Indices leading to an item through nested `children`, starting from the top-level list.
+pub type ItemPath = Vec<usize>;
+
+pub fn get<'a>(items: &'a [TodoItem], path: &[usize]) -> Option<&'a TodoItem> {
+    let (first, rest) = path.split_first()?;
+    let item = items.get(*first)?;
+    if rest.is_empty() {
+        Some(item)
+    } else {
+        get(&item.children, rest)
+    }
+}
+
+pub fn get_mut<'a>(items: &'a mut [TodoItem], path: &[usize]) -> Option<&'a mut TodoItem> {
+    let (first, rest) = path.split_first()?;
+    let item = items.get_mut(*first)?;
+    if rest.is_empty() {
+        Some(item)
+    } else {
+        get_mut(&mut item.children, rest)
+    }
+}
+
+/// Items at the level of `parent`, the top-level list for an empty path.
+pub fn children<'a>(items: &'a [TodoItem], parent: &[usize]) -> Option<&'a [TodoItem]> {
+    if parent.is_empty() {
+        return Some(items);
+    }
+    get(items, parent).map(|item| item.children.as_slice())
+}
+
+pub fn children_mut<'a>(
+    items: &'a mut Vec<TodoItem>,
+    parent: &[usize],
+) -> Option<&'a mut Vec<TodoItem>> {
+    if parent.is_empty() {
+        return Some(items);
+    }
+    get_mut(items, parent).map(|item| &mut item.children)
+}
+
+/// Remove an item, along with its children.
+pub fn remove(items: &mut Vec<TodoItem>, path: &[usize]) -> Option<TodoItem> {
+    match path.split_last()? {
+        (last, []) => (*last < items.len()).then(|| items.remove(*last)),
+        (last, parent) => {
+            let parent = get_mut(items, parent)?;
+            (*last < parent.children.len()).then(|| parent.children.remove(*last))
+        }
+    }
+}
+
+/// Path of the first item, depth first, satisfying `predicate`.
+pub fn find(items: &[TodoItem], predicate: &impl Fn(&TodoItem) -> bool) -> Option<ItemPath> {
+    items.iter().enumerate().find_map(|(i, item)| {
+        if predicate(item) {
+            return Some(vec![i]);
+        }
+        let mut path = find(&item.children, predicate)?;
+        path.insert(0, i);
+        Some(path)
+    })
+}
+
+/// Whether the item or one of its descendants satisfies `predicate`.
+pub fn any(item: &TodoItem, predicate: &impl Fn(&TodoItem) -> bool) -> bool {
+    predicate(item) || item.children.iter().any(|child| any(child, predicate))
+}
+
+pub fn for_each(items: &[TodoItem], f: &mut impl FnMut(&TodoItem)) {
+    for item in items {
+        f(item);
+        for_each(&item.children, f);
+    }
+}
+
+pub fn for_each_mut(items: &mut [TodoItem], f: &mut impl FnMut(&mut TodoItem)) {
+    for item in items {
+        f(item);
+        for_each_mut(&mut item.children, f);
+    }
+}
+
+/// Paths of the rows to display, depth first, skipping the children of collapsed items and the
+/// subtrees without any item satisfying `keep`.
+pub fn flatten(items: &[TodoItem], keep: &impl Fn(&TodoItem) -> bool) -> Vec<ItemPath> {
+    let mut rows = Vec::new();
+    flatten_into(items, keep, &mut Vec::new(), &mut rows);
+    rows
+}
+
+fn flatten_into(
+    items: &[TodoItem],
+    keep: &impl Fn(&TodoItem) -> bool,
+    path: &mut ItemPath,
+    rows: &mut Vec<ItemPath>,
+) {
+    for (i, item) in items.iter().enumerate() {
+        if !any(item, keep) {
+            continue;
+        }
+        path.push(i);
+        rows.push(path.clone());
+        if !item.collapsed {
+            flatten_into(&item.children, keep, path, rows);
+        }
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::Status;
+
+    fn item(name: &str, children: Vec<TodoItem>) -> TodoItem {
+        let mut item = TodoItem::new(Status::Todo, name, "");
+        item.children = children;
+        item
+    }
+
+    #[test]
+    fn test_flatten() {
+        let mut items = vec![
+            item(
+                "a",
+                vec![item("a1", vec![item("a1x", vec![])]), item("a2", vec![])],
+            ),
+            item("b", vec![item("b1", vec![])]),
+        ];
+        let all = |_: &TodoItem| true;
+        assert_eq!(
+            flatten(&items, &all),
+            [
+                vec![0],
+                vec![0, 0],
+                vec![0, 0, 0],
+                vec![0, 1],
+                vec![1],
+                vec![1, 0]
+            ]
+        );
+
+        // a collapsed parent hides its children
+        items[0].collapsed = true;
+        assert_eq!(flatten(&items, &all), [vec![0], vec![1], vec![1, 0]]);
+
+        // parents of matching children stay visible, other subtrees are hidden
+        items[0].collapsed = false;
+        let x = |item: &TodoItem| item.todo.ends_with('x');
+        assert_eq!(flatten(&items, &x), [vec![0], vec![0, 0], vec![0, 0, 0]]);
+        assert_eq!(get(&items, &[0, 0, 0]).unwrap().todo, "a1x");
+        assert_eq!(find(&items, &|item| item.todo == "b1"), Some(vec![1, 0]));
+    }
+}
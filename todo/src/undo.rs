@@ -0,0 +1,68 @@
+use std::collections::VecDeque;
+
+use crate::base::TodoItem;
+
+/// Default number of mutations that can be undone.
+pub const UNDO_CAPACITY: usize = 100;
+
+/// Content of both lists at a point in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub todo: Vec<TodoItem>,
+    pub history: Vec<TodoItem>,
+}
+
+/// Bounded undo/redo stacks of list snapshots.
+pub struct UndoStack {
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+    capacity: usize,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::new(UNDO_CAPACITY)
+    }
+}
+
+impl UndoStack {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Record the state preceding a mutation, dropping the oldest entry when full.
+    pub fn record(&mut self, before: Snapshot) {
+        self.push_undo(before);
+        self.redo.clear();
+    }
+
+    fn push_undo(&mut self, snapshot: Snapshot) {
+        if self.undo.len() == self.capacity {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(snapshot);
+    }
+
+    /// Return the state to restore, saving `current` so it can be redone.
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        Some(previous)
+    }
+
+    /// Return the state undone last, saving `current` so it can be undone again.
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.push_undo(current);
+        Some(next)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
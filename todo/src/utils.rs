@@ -1,30 +1,344 @@
 use crate::base::TodoItem;
+use crate::tree;
+use std::error::Error;
 use std::fs;
-use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
 
 const CACHE_FOLDER: &str = ".todos";
+pub const DEFAULT_TOPIC: &str = "general";
+const DEFAULT_HISTORY: &str = "history";
+const HISTORY_SUFFIX: &str = ".history";
+const ARCHIVE_FOLDER: &str = "archive";
+const LAST_ID_SUFFIX: &str = ".last-id";
 
-pub fn load_todo_items(file_name: &str) -> Result<Vec<TodoItem>, Box<dyn std::error::Error>> {
-    _ = fs::create_dir_all(CACHE_FOLDER)?;
-    let file_path = Path::new(CACHE_FOLDER).join(Path::new(file_name));
-    if !file_path.exists() {
-        _ = fs::File::create(&file_path);
+/// File holding the open tasks of a topic.
+pub fn topic_file(topic: &str) -> String {
+    format!("{topic}.json")
+}
+
+/// File holding the flushed tasks of a topic.
+///
+/// The default topic keeps using the original `history.json`.
+pub fn history_file(topic: &str) -> String {
+    if topic == DEFAULT_TOPIC {
+        format!("{DEFAULT_HISTORY}.json")
+    } else {
+        format!("{topic}{HISTORY_SUFFIX}.json")
+    }
+}
+
+/// File holding the tasks of a topic archived for a month, written as `YYYY-MM`.
+///
+/// Like the history, the default topic keeps the month alone as a name.
+pub fn archive_file(topic: &str, month: &str) -> String {
+    if topic == DEFAULT_TOPIC {
+        format!("{ARCHIVE_FOLDER}/{month}.json")
+    } else {
+        format!("{ARCHIVE_FOLDER}/{topic}.{month}.json")
+    }
+}
+
+/// File holding the highest id ever given in a topic.
+pub fn last_id_file(topic: &str) -> String {
+    format!("{topic}{LAST_ID_SUFFIX}")
+}
+
+/// Check that a topic name can be used as a file stem in the cache folder.
+pub fn is_valid_topic(name: &str) -> bool {
+    !name.is_empty()
+        && name != DEFAULT_HISTORY
+        && !name.ends_with(HISTORY_SUFFIX)
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Folder holding the topic files.
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Use `dir` when given, else the nearest `.todos` from the working directory up to the
+    /// git root, else the global store in `$XDG_DATA_HOME/todo`.
+    pub fn discover(dir: Option<PathBuf>) -> Self {
+        if let Some(dir) = dir {
+            return Self::new(dir);
+        }
+        let local = std::env::current_dir()
+            .ok()
+            .and_then(|cwd| find_cache_folder(&cwd));
+        match local {
+            Some(root) => Self::new(root),
+            None => Self::new(global_folder()),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Folder of the project owning a `.todos` store, the working directory for other stores.
+    pub fn project_root(&self) -> PathBuf {
+        match self.root.parent() {
+            Some(parent) if self.root.ends_with(CACHE_FOLDER) => parent.to_path_buf(),
+            _ => std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        }
+    }
+
+    /// List every topic stored in the cache folder, sorted by name.
+    pub fn list_topics(&self) -> Vec<String> {
+        let mut topics: Vec<String> = fs::read_dir(&self.root)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_file())
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+                    .filter(|stem| stem != DEFAULT_HISTORY && !stem.ends_with(HISTORY_SUFFIX))
+                    .collect()
+            })
+            .unwrap_or_default();
+        topics.sort();
+        topics
+    }
+
+    /// Months with archived tasks of a topic, as `YYYY-MM`, sorted.
+    pub fn list_archives(&self, topic: &str) -> Vec<String> {
+        let mut months: Vec<String> = fs::read_dir(self.root.join(ARCHIVE_FOLDER))
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter_map(|entry| Some(entry.file_name().to_str()?.to_string()))
+                    .filter_map(|name| {
+                        let stem = name.strip_suffix(".json")?;
+                        let month = if topic == DEFAULT_TOPIC {
+                            stem
+                        } else {
+                            stem.strip_prefix(topic)?.strip_prefix('.')?
+                        };
+                        let first_day = format!("{month}-01");
+                        chrono::NaiveDate::parse_from_str(&first_day, "%Y-%m-%d").ok()?;
+                        Some(month.to_string())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        months.sort();
+        months
+    }
+
+    pub fn delete_archive(&self, topic: &str, month: &str) -> std::io::Result<()> {
+        fs::remove_file(self.root.join(archive_file(topic, month)))
+    }
+
+    /// Rename the files of a topic, archives included.
+    pub fn rename_topic(&self, from: &str, to: &str) -> std::io::Result<()> {
+        let folder = &self.root;
+        fs::rename(folder.join(topic_file(from)), folder.join(topic_file(to)))?;
+        let history = folder.join(history_file(from));
+        if history.exists() {
+            fs::rename(history, folder.join(history_file(to)))?;
+        }
+        let last_id = folder.join(last_id_file(from));
+        if last_id.exists() {
+            fs::rename(last_id, folder.join(last_id_file(to)))?;
+        }
+        for month in self.list_archives(from) {
+            fs::rename(
+                folder.join(archive_file(from, &month)),
+                folder.join(archive_file(to, &month)),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Delete the files of a topic, archives included.
+    pub fn delete_topic(&self, topic: &str) -> std::io::Result<()> {
+        let folder = &self.root;
+        fs::remove_file(folder.join(topic_file(topic)))?;
+        let history = folder.join(history_file(topic));
+        if history.exists() {
+            fs::remove_file(history)?;
+        }
+        let last_id = folder.join(last_id_file(topic));
+        if last_id.exists() {
+            fs::remove_file(last_id)?;
+        }
+        for month in self.list_archives(topic) {
+            self.delete_archive(topic, &month)?;
+        }
+        Ok(())
     }
-    let mut json_content = fs::read_to_string(file_path).unwrap_or_else(|_| "[]".to_string());
-    // Parse the JSON
-    if json_content.len() == 0 {
-        json_content = "[]".to_string();
+
+    pub fn load_todo_items(&self, file_name: &str) -> Result<Vec<TodoItem>, Box<dyn Error>> {
+        let file_path = self.root.join(Path::new(file_name));
+        if let Some(folder) = file_path.parent() {
+            fs::create_dir_all(folder)?;
+        }
+        if !file_path.exists() {
+            _ = fs::File::create(&file_path);
+        }
+        let mut json_content = fs::read_to_string(file_path).unwrap_or_else(|_| "[]".to_string());
+        // Parse the JSON
+        if json_content.is_empty() {
+            json_content = "[]".to_string();
+        }
+        let todo_items: Vec<TodoItem> = serde_json::from_str(&json_content)?;
+
+        Ok(todo_items)
+    }
+
+    /// Highest id ever given in a topic.
+    ///
+    /// Stores written before it was kept fall back on the highest id of the archives, the ones
+    /// of the lists being counted by `base::assign_ids`.
+    pub fn load_last_id(&self, topic: &str) -> Result<u64, Box<dyn Error>> {
+        match fs::read_to_string(self.root.join(last_id_file(topic))) {
+            Ok(content) => Ok(content.trim().parse()?),
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                let mut last_id = 0;
+                for month in self.list_archives(topic) {
+                    let items = self.load_todo_items(&archive_file(topic, &month))?;
+                    tree::for_each(&items, &mut |item| last_id = last_id.max(item.id));
+                }
+                Ok(last_id)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Save the highest id given in a topic, keeping a higher one saved by another process.
+    pub fn save_last_id(&self, topic: &str, last_id: u64) -> Result<(), Box<dyn Error>> {
+        let last_id = last_id.max(self.load_last_id(topic)?);
+        fs::create_dir_all(&self.root)?;
+        fs::write(self.root.join(last_id_file(topic)), format!("{last_id}\n"))?;
+        Ok(())
+    }
+
+    /// Write a list atomically: the JSON goes to a temporary file which then replaces the
+    /// previous one, itself kept as a `.bak` copy.
+    pub fn save_list(&self, items: &[TodoItem], name: &str) -> std::io::Result<()> {
+        let path = self.root.join(Path::new(name));
+        // archives are in a subfolder
+        let folder = path.parent().unwrap_or(&self.root);
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(name);
+        fs::create_dir_all(folder)?;
+        let tmp_path = folder.join(format!(".{file_name}.tmp"));
+        let file = fs::File::create(&tmp_path)?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, items)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        drop(writer);
+        if path.exists() {
+            fs::copy(&path, folder.join(format!("{file_name}.bak")))?;
+        }
+        fs::rename(tmp_path, path)
+    }
+}
+
+/// Walk up from `start` looking for a `.todos` folder, stopping at the git root.
+pub fn find_cache_folder(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        let candidate = dir.join(CACHE_FOLDER);
+        if candidate.is_dir() {
+            return Some(candidate);
+        }
+        if dir.join(".git").exists() {
+            return None;
+        }
+    }
+    None
+}
+
+/// Store shared by every project, `$XDG_DATA_HOME/todo` or `~/.local/share/todo` by default.
+fn global_folder() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("todo")
+}
+
+/// Temporary folder unique to a test, deleted once dropped, even when the test fails.
+#[cfg(test)]
+pub struct TempFolder {
+    path: PathBuf,
+}
+
+#[cfg(test)]
+impl TempFolder {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("todo-{name}-{}", std::process::id()));
+        // left over by a killed run
+        _ = fs::remove_dir_all(&path);
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
     }
-    let todo_items: Vec<TodoItem> = serde_json::from_str(&json_content)?;
 
-    Ok(todo_items)
+    /// Store in the `.todos` folder of the temporary folder.
+    pub fn store(&self) -> Store {
+        Store::new(self.path.join(CACHE_FOLDER))
+    }
 }
 
-pub fn save_list(items: Vec<TodoItem>, name: &str) {
-    let path = Path::new(CACHE_FOLDER).join(Path::new(name));
-    let file = fs::File::create(path).unwrap();
-    let mut writer = BufWriter::new(file);
-    _ = serde_json::to_writer_pretty(&mut writer, &items);
-    _ = writer.flush();
+#[cfg(test)]
+impl Drop for TempFolder {
+    fn drop(&mut self) {
+        _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::Status;
+
+    #[test]
+    fn test_find_cache_folder_stops_at_git_root() {
+        let folder = TempFolder::new("discover");
+        let root = folder.path();
+        let project = root.join("project");
+        let nested = project.join("src").join("module");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(project.join(".git")).unwrap();
+        // a store above the git root belongs to another project
+        fs::create_dir_all(root.join(CACHE_FOLDER)).unwrap();
+        assert_eq!(find_cache_folder(&nested), None);
+
+        fs::create_dir_all(project.join(CACHE_FOLDER)).unwrap();
+        assert_eq!(find_cache_folder(&nested), Some(project.join(CACHE_FOLDER)));
+    }
+
+    #[test]
+    fn test_save_list_keeps_a_backup() {
+        let folder = TempFolder::new("save");
+        let store = folder.store();
+        let root = store.root();
+        let first = vec![TodoItem::new(Status::Todo, "first", "")];
+        let second = vec![TodoItem::new(Status::Todo, "second", "")];
+        let name = archive_file(DEFAULT_TOPIC, "2026-01");
+
+        store.save_list(&first, &name).unwrap();
+        assert!(!root.join("archive").join("2026-01.json.bak").exists());
+        store.save_list(&second, &name).unwrap();
+        assert_eq!(store.load_todo_items(&name).unwrap(), second);
+        let backup = root.join("archive").join("2026-01.json.bak");
+        let backup: Vec<TodoItem> =
+            serde_json::from_str(&fs::read_to_string(backup).unwrap()).unwrap();
+        assert_eq!(backup, first);
+        // the temporary file was renamed over the list
+        assert!(!root.join("archive").join(".2026-01.json.tmp").exists());
+    }
 }
@@ -0,0 +1,39 @@
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Notifications of the changes made to the files of a store by other processes.
+pub struct StoreWatcher {
+    // events stop once the watcher is dropped
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+}
+
+impl StoreWatcher {
+    pub fn new(root: &Path) -> notify::Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(root, RecursiveMode::NonRecursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    /// Whether one of the files was written since the last call, without blocking.
+    pub fn changed(&self, file_names: &[String]) -> bool {
+        let mut changed = false;
+        for event in self.events.try_iter().flatten() {
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            changed |= event.paths.iter().any(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| file_names.iter().any(|file| file == name))
+            });
+        }
+        changed
+    }
+}